@@ -0,0 +1,112 @@
+//! Build Script
+//!
+//! Writes our linker script fragments to `OUT_DIR`, and adds `OUT_DIR` to the
+//! linker search path, so an application can use them with
+//! `INCLUDE binary_info.x` or `-Tbinary_info.x`.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// The linker script fragment, with `{chip}` and `{header_limit}`
+/// placeholders.
+const TEMPLATE: &str = r#"/* Picotool 'Binary Info' sections for cortex-m-rt on the {chip}.
+ *
+ * Generated by the rp-binary-info build script. Either add
+ * `INCLUDE binary_info.x` to the end of your memory.x, or add
+ * `-Tbinary_info.x` to your linker arguments, before `-Tlink.x`. Our
+ * `_stext` must be seen before the default one in cortex-m-rt's link.x.
+ */
+
+SECTIONS {
+    /* ### Picotool 'Binary Info' Header Block
+     *
+     * Picotool only searches the start of Flash for this block, but that's
+     * where our vector table is. We squeeze in this block after the vector
+     * table (and anything else inserted after it), but before .text.
+     */
+    .bi_header : ALIGN(4)
+    {
+        KEEP(*(.bi_header));
+        /* Keep this block a nice round size */
+        . = ALIGN(4);
+    } > FLASH
+} INSERT BEFORE .text;
+
+/* Move _stext, to make room for our new section. The start of .text must
+ * still be aligned as its contents need. */
+_stext = ALIGN(ADDR(.bi_header) + SIZEOF(.bi_header), ALIGNOF(.text));
+
+SECTIONS {
+    /* ### Picotool 'Binary Info' Entries
+     *
+     * Picotool looks through this block (as we have pointers to it in our
+     * header) to find interesting information.
+     */
+    .bi_entries : ALIGN(4)
+    {
+        /* We put this in the header */
+        __bi_entries_start = .;
+        /* Here are the entries */
+        KEEP(*(.bi_entries));
+        /* Keep this block a nice round size */
+        . = ALIGN(4);
+        /* We put this in the header */
+        __bi_entries_end = .;
+    } > FLASH
+} INSERT AFTER .text;
+
+SECTIONS {
+    /* ### The end of the program in Flash
+     *
+     * This comes after the RAM sections, so the Flash region's location
+     * counter has already passed everything which goes in Flash - the .data
+     * initialisers, and anything after them like .gnu.sgstubs.
+     */
+    .bi_flash_end (NOLOAD) :
+    {
+        PROVIDE(__flash_binary_end = .);
+    } > FLASH
+} INSERT AFTER .bss;
+
+ASSERT(SIZEOF(.bi_header) != 0, "
+ERROR(rp-binary-info): the .bi_header section is empty. Did you forget to
+use the binary_info_header! macro?");
+
+ASSERT(ADDR(.bi_header) + SIZEOF(.bi_header) <= {header_limit}, "
+ERROR(rp-binary-info): the .bi_header section ends after {header_limit},
+so picotool will not find it on the {chip}.");
+"#;
+
+/// The chips we have fragments for: file name suffix, display name, and the
+/// address at which picotool stops looking for the header.
+const CHIPS: [(&str, &str, &str); 2] = [
+    // Picotool searches the 256 bytes after the 256 byte boot2 block
+    ("rp2040", "RP2040", "0x10000200"),
+    // Picotool searches the first 4 KiB, alongside the image definition
+    ("rp2350", "RP2350", "0x10001000"),
+];
+
+fn main() {
+    let out = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+    let default_chip = if env::var_os("CARGO_FEATURE_RP2350").is_some() {
+        "rp2350"
+    } else {
+        "rp2040"
+    };
+
+    for (suffix, chip, header_limit) in CHIPS.iter() {
+        let script = TEMPLATE
+            .replace("{chip}", chip)
+            .replace("{header_limit}", header_limit);
+        fs::write(out.join(format!("binary_info_{}.x", suffix)), &script).unwrap();
+        if *suffix == default_chip {
+            fs::write(out.join("binary_info.x"), &script).unwrap();
+        }
+    }
+
+    println!("cargo:rustc-link-search={}", out.display());
+    println!("cargo:rerun-if-changed=build.rs");
+}
+
+// End of file
@@ -0,0 +1,882 @@
+//! # rp-binary-info
+//!
+//! Prints the 'Binary Info' in an ELF, UF2 or BIN file, much like
+//! `picotool info -a`, but without needing `picotool` or `libusb`.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fmt::Write;
+use std::process;
+
+use rp_binary_info::parse::{BinaryInfo, Chip, Entry};
+use rp_binary_info::{elf, parse, uf2};
+
+/// Where a `.bin` file is loaded, unless the user says otherwise
+const DEFAULT_BIN_BASE: u32 = 0x1000_0000;
+
+/// The width of the labels in our output
+const LABEL_WIDTH: usize = 20;
+
+fn main() {
+    let mut base_address = DEFAULT_BIN_BASE;
+    let mut json = false;
+    let mut files = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                print_usage();
+                return;
+            }
+            "--json" => json = true,
+            "--base" => match args.next().as_deref().and_then(parse_u32) {
+                Some(value) => base_address = value,
+                None => fail("--base needs an address, like 0x10000000"),
+            },
+            _ => files.push(arg),
+        }
+    }
+
+    if files.is_empty() {
+        print_usage();
+        process::exit(1);
+    }
+
+    if json {
+        let mut all = BTreeMap::new();
+        for filename in &files {
+            match load(filename, base_address) {
+                Ok((info, _)) => {
+                    all.insert(filename.as_str(), info);
+                }
+                Err(error) => fail(&format!("{}: {}", filename, error)),
+            }
+        }
+        match render_json(&all) {
+            Ok(output) => println!("{}", output),
+            Err(error) => fail(&error.to_string()),
+        }
+        return;
+    }
+
+    for (idx, filename) in files.iter().enumerate() {
+        if idx != 0 {
+            println!();
+        }
+        match load(filename, base_address) {
+            Ok((info, chip)) => {
+                println!("File {}:", filename);
+                print!("{}", render(&info, chip));
+            }
+            Err(error) => fail(&format!("{}: {}", filename, error)),
+        }
+    }
+}
+
+fn print_usage() {
+    println!("Usage: rp-binary-info [--json] [--base <address>] <file>...");
+    println!();
+    println!("Prints the Binary Info in each ELF, UF2 or BIN file.");
+    println!();
+    println!("  --json            print the Binary Info as JSON, keyed by file name");
+    println!(
+        "  --base <address>  where a BIN file is loaded (default {:#010x})",
+        DEFAULT_BIN_BASE
+    );
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("rp-binary-info: {}", message);
+    process::exit(1);
+}
+
+/// Parse a decimal or `0x` prefixed hexadecimal number.
+fn parse_u32(value: &str) -> Option<u32> {
+    match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => u32::from_str_radix(&hex.replace('_', ""), 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+/// Load a file, working out what kind of file it is from its contents.
+///
+/// We can only tell which chip the file is for if it is a UF2 file, in which
+/// case we also warn if `picotool` would not find the header.
+fn load(filename: &str, base_address: u32) -> Result<(BinaryInfo, Option<Chip>), Box<dyn Error>> {
+    let data = std::fs::read(filename)?;
+    if data.starts_with(&elf::ELF_MAGIC) {
+        Ok((elf::parse(&data)?, None))
+    } else if data.len() >= 8
+        && data[0..4] == uf2::MAGIC_START0.to_le_bytes()
+        && data[4..8] == uf2::MAGIC_START1.to_le_bytes()
+    {
+        let image = uf2::read(&data)?;
+        let base_address = image.memory.base_address().ok_or(uf2::Error::Empty)?;
+        let info = parse::parse_memory(&image.memory, base_address)?;
+        let chip = image.chip();
+        if let Some(Err(error)) = chip.map(|chip| info.check_placement(chip, base_address)) {
+            eprintln!("rp-binary-info: {}: warning: {}", filename, error);
+        }
+        Ok((info, chip))
+    } else {
+        Ok((parse::parse(&data, base_address)?, None))
+    }
+}
+
+/// Get the name of a GPIO function, as used in `PinsWithFunction` and
+/// `Pins64WithFunction` entries. Unless we know otherwise, we assume the
+/// RP2040.
+fn function_name(chip: Option<Chip>, function: u8) -> String {
+    let name = match (chip.unwrap_or(Chip::Rp2040), function) {
+        (Chip::Rp2040, 0) => "XIP",
+        (Chip::Rp2350, 0) => "HSTX",
+        (_, 1) => "SPI",
+        (_, 2) => "UART",
+        (_, 3) => "I2C",
+        (_, 4) => "PWM",
+        (_, 5) => "SIO",
+        (_, 6) => "PIO0",
+        (_, 7) => "PIO1",
+        (Chip::Rp2040, 8) => "GPCK",
+        (Chip::Rp2040, 9) => "USB",
+        (Chip::Rp2350, 8) => "PIO2",
+        (Chip::Rp2350, 9) => "GPCK",
+        (Chip::Rp2350, 10) => "USB",
+        (Chip::Rp2350, 11) => "UART (AUX)",
+        _ => return format!("function {}", function),
+    };
+    name.to_string()
+}
+
+/// Get the peripheral (e.g. `UART0`) a pin is connected to for the given
+/// GPIO function, and the signal (e.g. `TX`) it carries, where the pin
+/// decides them. Otherwise, we just give the name of the function.
+fn pin_signal(chip: Option<Chip>, function: u8, pin: u8) -> (String, Option<&'static str>) {
+    let pin = usize::from(pin);
+    match function {
+        1 => (
+            format!("SPI{}", (pin >> 3) & 1),
+            Some(["RX", "CSn", "SCK", "TX"][pin & 3]),
+        ),
+        2 => (
+            format!("UART{}", ((pin + 4) >> 3) & 1),
+            Some(["TX", "RX", "CTS", "RTS"][pin & 3]),
+        ),
+        3 => (
+            format!("I2C{}", (pin >> 1) & 1),
+            Some(["SDA", "SCL"][pin & 1]),
+        ),
+        4 => {
+            // The RP2350 has four more PWM slices for its extra pins
+            let slice = if pin < 32 {
+                (pin >> 1) & 7
+            } else {
+                8 + ((pin - 32) >> 1)
+            };
+            (format!("PWM{}", slice), Some(["A", "B"][pin & 1]))
+        }
+        _ => (function_name(chip, function), None),
+    }
+}
+
+/// Get the label for an `RP` tagged string, if we print it in one of our
+/// standard sections.
+fn rp_string_label(id: u32) -> Option<&'static str> {
+    let label = match id {
+        rp_binary_info::ID_RP_PROGRAM_NAME => "name",
+        rp_binary_info::ID_RP_PROGRAM_VERSION_STRING => "version",
+        rp_binary_info::ID_RP_PROGRAM_URL => "web site",
+        rp_binary_info::ID_RP_PROGRAM_DESCRIPTION => "description",
+        rp_binary_info::ID_RP_PROGRAM_FEATURE => "features",
+        rp_binary_info::ID_RP_SDK_VERSION => "sdk version",
+        rp_binary_info::ID_RP_PICO_BOARD => "pico_board",
+        rp_binary_info::ID_RP_BOOT2_NAME => "boot2_name",
+        rp_binary_info::ID_RP_PROGRAM_BUILD_DATE_STRING => "build date",
+        rp_binary_info::ID_RP_PROGRAM_BUILD_ATTRIBUTE => "build attributes",
+        _ => return None,
+    };
+    Some(label)
+}
+
+/// Describe the Binary Info from one file, the way we print it.
+fn render(info: &BinaryInfo, chip: Option<Chip>) -> String {
+    let mut out = String::new();
+    write_info(&mut out, info, chip);
+    out
+}
+
+/// Describe the Binary Info from each file as JSON, keyed by file name.
+fn render_json(all: &BTreeMap<&str, BinaryInfo>) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(all)
+}
+
+fn write_info(out: &mut String, info: &BinaryInfo, chip: Option<Chip>) {
+    let rp = rp_binary_info::TAG_RASPBERRY_PI;
+    let mut groups_done = BTreeSet::new();
+
+    write_heading(out, "Program Information");
+    write_strings(out, info, rp, rp_binary_info::ID_RP_PROGRAM_NAME);
+    write_strings(out, info, rp, rp_binary_info::ID_RP_PROGRAM_VERSION_STRING);
+    write_strings(out, info, rp, rp_binary_info::ID_RP_PROGRAM_DESCRIPTION);
+    write_strings(out, info, rp, rp_binary_info::ID_RP_PROGRAM_URL);
+    write_strings(out, info, rp, rp_binary_info::ID_RP_PROGRAM_FEATURE);
+    write_groups(
+        out,
+        info,
+        rp,
+        rp_binary_info::ID_RP_PROGRAM_FEATURE,
+        1,
+        &mut groups_done,
+    );
+    if let Some(end) = info.find_int(rp, rp_binary_info::ID_RP_BINARY_END) {
+        write_field(out, 1, "binary end", &format!("{:#010x}", end));
+    }
+
+    write_pins(out, info, chip);
+    write_block_devices(out, info);
+    write_config(out, info);
+
+    write_heading(out, "Build Information");
+    write_strings(out, info, rp, rp_binary_info::ID_RP_SDK_VERSION);
+    write_strings(out, info, rp, rp_binary_info::ID_RP_PICO_BOARD);
+    write_strings(out, info, rp, rp_binary_info::ID_RP_BOOT2_NAME);
+    write_strings(
+        out,
+        info,
+        rp,
+        rp_binary_info::ID_RP_PROGRAM_BUILD_DATE_STRING,
+    );
+    write_strings(out, info, rp, rp_binary_info::ID_RP_PROGRAM_BUILD_ATTRIBUTE);
+
+    write_other(out, info, &mut groups_done);
+
+    write_heading(out, "Metadata");
+    if let Some(chip) = chip {
+        write_field(out, 1, "chip", &chip.to_string());
+    }
+    write_field(
+        out,
+        1,
+        "header address",
+        &format!("{:#010x}", info.header_address),
+    );
+    for mapping in &info.mapping_table {
+        write_field(
+            out,
+            1,
+            "mapping",
+            &format!(
+                "{:#010x} -> {:#010x}..{:#010x}",
+                mapping.source_addr_start, mapping.dest_addr_start, mapping.dest_addr_end
+            ),
+        );
+    }
+}
+
+/// Write the title of a section, after a blank line.
+fn write_heading(out: &mut String, title: &str) {
+    out.push('\n');
+    out.push_str(title);
+    out.push('\n');
+}
+
+/// Write a labelled value, lined up with everything else.
+fn write_field(out: &mut String, indent: usize, label: &str, value: &str) {
+    let label = format!("{}{}:", " ".repeat(indent), label);
+    // Writing to a `String` cannot fail
+    let _ = writeln!(out, "{:<width$} {}", label, value, width = LABEL_WIDTH);
+}
+
+/// Write another value for the field above, lined up under its first value.
+fn write_more(out: &mut String, value: &str) {
+    let _ = writeln!(out, "{:<width$} {}", "", value, width = LABEL_WIDTH);
+}
+
+/// Write every `RP` tagged string with the given ID.
+fn write_strings(out: &mut String, info: &BinaryInfo, tag: u16, id: u32) {
+    let label = rp_string_label(id).unwrap_or("string");
+    for (idx, value) in strings(info, tag, id).enumerate() {
+        if idx == 0 {
+            write_field(out, 1, label, value);
+        } else {
+            write_more(out, value);
+        }
+    }
+}
+
+/// Iterate through the values of every string entry with the given tag and
+/// ID.
+fn strings(info: &BinaryInfo, tag: u16, id: u32) -> impl Iterator<Item = &str> {
+    info.entries.iter().filter_map(move |entry| match entry {
+        Entry::IdAndString {
+            tag: t,
+            id: i,
+            value,
+        } if *t == tag && *i == id => Some(value.as_str()),
+        _ => None,
+    })
+}
+
+/// Write every group inside the given parent, along with its members.
+///
+/// Each group is only printed once, so a group which is (directly or
+/// indirectly) its own parent doesn't send us round in circles. `done` holds
+/// the tag and ID of every group printed so far.
+fn write_groups(
+    out: &mut String,
+    info: &BinaryInfo,
+    parent_tag: u16,
+    parent_id: u32,
+    indent: usize,
+    done: &mut BTreeSet<(u16, u32)>,
+) {
+    for entry in &info.entries {
+        if let Entry::NamedGroup {
+            parent_tag: pt,
+            parent_id: pi,
+            flags,
+            group_tag,
+            group_id,
+            label,
+        } = entry
+        {
+            if *pt != parent_tag || *pi != parent_id || !done.insert((*group_tag, *group_id)) {
+                continue;
+            }
+            let mut members: Vec<String> = info
+                .entries
+                .iter()
+                .filter_map(|member| match member {
+                    Entry::IdAndString { tag, id, value } if tag == group_tag && id == group_id => {
+                        Some(value.clone())
+                    }
+                    Entry::IdAndInt { tag, id, value } if tag == group_tag && id == group_id => {
+                        Some(value.to_string())
+                    }
+                    _ => None,
+                })
+                .collect();
+            if members.is_empty() && flags & rp_binary_info::entry::NamedGroup::SHOW_IF_EMPTY == 0 {
+                continue;
+            }
+            if flags & rp_binary_info::entry::NamedGroup::SORT_ALPHA != 0 {
+                members.sort();
+            }
+            if flags & rp_binary_info::entry::NamedGroup::SEPARATE_COMMAS != 0 {
+                write_field(out, indent, label, &members.join(", "));
+            } else {
+                write_field(
+                    out,
+                    indent,
+                    label,
+                    members.first().map_or("", |s| s.as_str()),
+                );
+                for member in members.iter().skip(1) {
+                    write_more(out, member);
+                }
+            }
+            write_groups(out, info, *group_tag, *group_id, indent + 1, done);
+        }
+    }
+}
+
+/// Some pins which are used together, like the TX and RX pins of a UART.
+struct PinGroup {
+    /// The peripheral (e.g. `UART0`) or function the pins are used for, or
+    /// the names given to them
+    name: String,
+    /// The signal each pin carries (e.g. `TX`), if we know them
+    signals: Vec<&'static str>,
+    pins: Vec<u32>,
+}
+
+impl PinGroup {
+    fn label(&self) -> String {
+        if self.signals.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{} {}", self.name, self.signals.join("/"))
+        }
+    }
+}
+
+/// Write the pins used for each function, and the pins given names.
+fn write_pins(out: &mut String, info: &BinaryInfo, chip: Option<Chip>) {
+    let mut functions: Vec<PinGroup> = Vec::new();
+    let mut names: Vec<PinGroup> = Vec::new();
+    let mut add_function = |chip: Option<Chip>, function: u8, pins: &[u8]| {
+        for &pin in pins {
+            let (name, signal) = pin_signal(chip, function, pin);
+            let pin = u32::from(pin);
+            let group = match functions.iter().position(|group| group.name == name) {
+                Some(idx) => &mut functions[idx],
+                None => {
+                    functions.push(PinGroup {
+                        name,
+                        signals: Vec::new(),
+                        pins: Vec::new(),
+                    });
+                    functions.last_mut().unwrap()
+                }
+            };
+            if !group.pins.contains(&pin) {
+                group.pins.push(pin);
+                group.signals.extend(signal);
+            }
+        }
+    };
+    for entry in &info.entries {
+        match entry {
+            Entry::PinsWithFunction { function, pins, .. } => add_function(chip, *function, pins),
+            Entry::Pins64WithFunction { function, pins, .. } => {
+                // Only the RP2350 has enough pins to need these
+                add_function(Some(Chip::Rp2350), *function, pins)
+            }
+            Entry::PinsWithName {
+                pin_mask, label, ..
+            } => names.push(PinGroup {
+                name: label.replace('|', "/"),
+                signals: Vec::new(),
+                pins: (0..32).filter(|pin| pin_mask & (1 << pin) != 0).collect(),
+            }),
+            Entry::Pins64WithName {
+                pin_mask, label, ..
+            } => names.push(PinGroup {
+                name: label.replace('|', "/"),
+                signals: Vec::new(),
+                pins: (0..64).filter(|pin| pin_mask & (1 << pin) != 0).collect(),
+            }),
+            _ => {}
+        }
+    }
+    let mut groups = functions;
+    groups.append(&mut names);
+    if groups.is_empty() {
+        return;
+    }
+    groups.sort_by_key(|group| group.pins.iter().min().copied());
+    write_heading(out, "Fixed Pin Information");
+    for group in groups {
+        let pins: Vec<String> = group.pins.iter().map(|pin| pin.to_string()).collect();
+        write_field(out, 1, &group.label(), &pins.join(", "));
+    }
+}
+
+/// Write every variable which `picotool config` could change.
+fn write_config(out: &mut String, info: &BinaryInfo) {
+    let mut first = true;
+    for entry in &info.entries {
+        let (label, value) = match entry {
+            Entry::PtrInt32WithName {
+                label,
+                value,
+                address,
+                ..
+            } => (label, int_with_limits(info, *value, *address)),
+            Entry::PtrStringWithName {
+                label,
+                value,
+                max_len,
+                ..
+            } => (label, format!("{:?} ({} byte buffer)", value, max_len)),
+            _ => continue,
+        };
+        if first {
+            write_heading(out, "Configuration");
+            first = false;
+        }
+        write_field(out, 1, label, &value);
+    }
+}
+
+/// Format an integer variable's value, along with any limits set on it.
+fn int_with_limits(info: &BinaryInfo, value: i32, address: u32) -> String {
+    let (min, max, bits) = info.int_limits(address).unwrap_or((i32::MIN, i32::MAX, 32));
+    let mut limits = Vec::new();
+    if min != i32::MIN {
+        limits.push(format!("min {}", min));
+    }
+    if max != i32::MAX {
+        limits.push(format!("max {}", max));
+    }
+    if bits != 32 {
+        limits.push(format!("{} bits", bits));
+    }
+    if limits.is_empty() {
+        value.to_string()
+    } else {
+        format!("{} ({})", value, limits.join(", "))
+    }
+}
+
+/// Write every block device.
+fn write_block_devices(out: &mut String, info: &BinaryInfo) {
+    use rp_binary_info::entry::BlockDevice;
+
+    let mut first = true;
+    for entry in &info.entries {
+        if let Entry::BlockDevice {
+            name,
+            address,
+            size,
+            flags,
+            ..
+        } = entry
+        {
+            if first {
+                write_heading(out, "Block Devices");
+                first = false;
+            }
+            let mut access = String::new();
+            for (flag, letter) in [
+                (BlockDevice::FLAG_READ, 'r'),
+                (BlockDevice::FLAG_WRITE, 'w'),
+                (BlockDevice::FLAG_REFORMAT, 'f'),
+            ] {
+                access.push(if flags & flag != 0 { letter } else { '-' });
+            }
+            let partition_table = match flags & (3 << 4) {
+                BlockDevice::FLAG_PT_MBR => "MBR",
+                BlockDevice::FLAG_PT_GPT => "GPT",
+                BlockDevice::FLAG_PT_NONE => "none",
+                _ => "unknown",
+            };
+            write_field(
+                out,
+                1,
+                name,
+                &format!(
+                    "{:#010x}..{:#010x} ({} bytes) {} partition table: {}",
+                    address,
+                    address.wrapping_add(*size),
+                    size,
+                    access,
+                    partition_table
+                ),
+            );
+        }
+    }
+}
+
+/// Write every entry not covered by one of the other sections, including
+/// any groups (and their members) which haven't been printed yet.
+fn write_other(out: &mut String, info: &BinaryInfo, groups_done: &mut BTreeSet<(u16, u32)>) {
+    let rp = rp_binary_info::TAG_RASPBERRY_PI;
+    let in_group = |tag: u16, id: u32| {
+        info.entries.iter().any(|entry| {
+            matches!(entry, Entry::NamedGroup { group_tag, group_id, .. } if *group_tag == tag && *group_id == id)
+        })
+    };
+
+    let mut lines = Vec::new();
+    for entry in &info.entries {
+        match entry {
+            Entry::IdAndString { tag, id, value } => {
+                if (*tag == rp && rp_string_label(*id).is_some()) || in_group(*tag, *id) {
+                    continue;
+                }
+                lines.push((format!("{:#06x}:{:#010x}", tag, id), value.clone()));
+            }
+            Entry::IdAndInt { tag, id, value } => {
+                if (*tag == rp && *id == rp_binary_info::ID_RP_BINARY_END) || in_group(*tag, *id) {
+                    continue;
+                }
+                lines.push((
+                    format!("{:#06x}:{:#010x}", tag, id),
+                    format!("{:#010x}", value),
+                ));
+            }
+            Entry::Raw { tag, address } => {
+                lines.push((
+                    format!("{:#06x}", tag),
+                    format!("raw data at {:#010x}", address),
+                ));
+            }
+            Entry::SizedData { tag, data } => {
+                let hex: Vec<String> = data.iter().map(|b| format!("{:02x}", b)).collect();
+                lines.push((format!("{:#06x}", tag), hex.join(" ")));
+            }
+            Entry::Unknown {
+                data_type,
+                tag,
+                address,
+            } => {
+                lines.push((
+                    format!("{:#06x}", tag),
+                    format!("unknown data type {} at {:#010x}", data_type, address),
+                ));
+            }
+            _ => {}
+        }
+    }
+    // Print groups from the top down, so each one is shown inside its
+    // parent. Anything left over after that is part of a loop of groups.
+    let parents: Vec<(u16, u32)> = info
+        .entries
+        .iter()
+        .filter_map(|entry| match entry {
+            Entry::NamedGroup {
+                parent_tag,
+                parent_id,
+                group_tag,
+                group_id,
+                ..
+            } if !groups_done.contains(&(*group_tag, *group_id)) => Some((*parent_tag, *parent_id)),
+            _ => None,
+        })
+        .collect();
+    if lines.is_empty() && parents.is_empty() {
+        return;
+    }
+    write_heading(out, "Other Information");
+    for (label, value) in lines {
+        write_field(out, 1, &label, &value);
+    }
+    let (top, rest): (Vec<_>, Vec<_>) = parents
+        .into_iter()
+        .partition(|(tag, id)| !in_group(*tag, *id));
+    for (parent_tag, parent_id) in top.into_iter().chain(rest) {
+        write_groups(out, info, parent_tag, parent_id, 1, groups_done);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rp_binary_info::parse::Mapping;
+
+    /// The same Binary Info as the library's sample image, plus some pins.
+    fn sample() -> BinaryInfo {
+        let rp = rp_binary_info::TAG_RASPBERRY_PI;
+        BinaryInfo {
+            header_address: 0x1000_0100,
+            entries: vec![
+                Entry::IdAndString {
+                    tag: rp,
+                    id: rp_binary_info::ID_RP_PROGRAM_NAME,
+                    value: "blinky".to_string(),
+                },
+                Entry::IdAndInt {
+                    tag: rp,
+                    id: rp_binary_info::ID_RP_BINARY_END,
+                    value: 0x1000_2000,
+                },
+                Entry::PtrInt32WithName {
+                    tag: 0x1234,
+                    id: 1,
+                    label: "baud".to_string(),
+                    value: -5,
+                    address: 0x2000_0000,
+                },
+                Entry::PtrStringWithName {
+                    tag: 0x1234,
+                    id: 2,
+                    label: "name".to_string(),
+                    value: "dev".to_string(),
+                    max_len: 8,
+                    address: 0x2000_0004,
+                },
+                Entry::PtrInt32Limits {
+                    tag: 0x1234,
+                    id: 1,
+                    address: 0x2000_0000,
+                    min: -10,
+                    max: 10,
+                    bits: 5,
+                },
+                Entry::SizedData {
+                    tag: 0x1234,
+                    data: vec![1, 2, 3],
+                },
+                Entry::PinsWithFunction {
+                    tag: rp,
+                    function: 2,
+                    pins: vec![0, 1],
+                },
+                Entry::PinsWithFunction {
+                    tag: rp,
+                    function: 3,
+                    pins: vec![4, 5],
+                },
+                Entry::PinsWithName {
+                    tag: rp,
+                    pin_mask: 0x30,
+                    label: "SDA|SCL".to_string(),
+                },
+                Entry::PinsWithFunction {
+                    tag: rp,
+                    function: 5,
+                    pins: vec![25],
+                },
+            ],
+            mapping_table: vec![Mapping {
+                source_addr_start: 0x1000_0380,
+                dest_addr_start: 0x2000_0000,
+                dest_addr_end: 0x2000_000c,
+            }],
+        }
+    }
+
+    #[test]
+    fn render_text() {
+        let expected = r#"
+Program Information
+ name:               blinky
+ binary end:         0x10002000
+
+Fixed Pin Information
+ UART0 TX/RX:        0, 1
+ I2C0 SDA/SCL:       4, 5
+ SDA/SCL:            4, 5
+ SIO:                25
+
+Configuration
+ baud:               -5 (min -10, max 10, 5 bits)
+ name:               "dev" (8 byte buffer)
+
+Build Information
+
+Other Information
+ 0x1234:             01 02 03
+
+Metadata
+ header address:     0x10000100
+ mapping:            0x10000380 -> 0x20000000..0x2000000c
+"#;
+        assert_eq!(render(&sample(), None), expected);
+    }
+
+    #[test]
+    fn render_as_json() {
+        let mut all = BTreeMap::new();
+        all.insert("blinky.elf", sample());
+        let expected = r#"{
+  "blinky.elf": {
+    "header_address": 268435712,
+    "entries": [
+      {
+        "data_type": "id_and_string",
+        "tag": 20562,
+        "id": 33758342,
+        "name": "program_name",
+        "value": "blinky"
+      },
+      {
+        "data_type": "id_and_int",
+        "tag": 20562,
+        "id": 1760847326,
+        "name": "binary_end",
+        "value": 268443648
+      },
+      {
+        "data_type": "ptr_int32_with_name",
+        "tag": 4660,
+        "id": 1,
+        "name": null,
+        "value": {
+          "label": "baud",
+          "value": -5,
+          "address": 536870912
+        }
+      },
+      {
+        "data_type": "ptr_string_with_name",
+        "tag": 4660,
+        "id": 2,
+        "name": null,
+        "value": {
+          "label": "name",
+          "value": "dev",
+          "max_len": 8,
+          "address": 536870916
+        }
+      },
+      {
+        "data_type": "ptr_int32_limits",
+        "tag": 4660,
+        "id": 1,
+        "name": null,
+        "value": {
+          "address": 536870912,
+          "min": -10,
+          "max": 10,
+          "bits": 5
+        }
+      },
+      {
+        "data_type": "sized_data",
+        "tag": 4660,
+        "id": null,
+        "name": null,
+        "value": [
+          1,
+          2,
+          3
+        ]
+      },
+      {
+        "data_type": "pins_with_function",
+        "tag": 20562,
+        "id": null,
+        "name": null,
+        "value": {
+          "function": 2,
+          "pins": [
+            0,
+            1
+          ]
+        }
+      },
+      {
+        "data_type": "pins_with_function",
+        "tag": 20562,
+        "id": null,
+        "name": null,
+        "value": {
+          "function": 3,
+          "pins": [
+            4,
+            5
+          ]
+        }
+      },
+      {
+        "data_type": "pins_with_name",
+        "tag": 20562,
+        "id": null,
+        "name": null,
+        "value": {
+          "pin_mask": 48,
+          "label": "SDA|SCL"
+        }
+      },
+      {
+        "data_type": "pins_with_function",
+        "tag": 20562,
+        "id": null,
+        "name": null,
+        "value": {
+          "function": 5,
+          "pins": [
+            25
+          ]
+        }
+      }
+    ],
+    "mapping_table": [
+      {
+        "source_addr_start": 268436352,
+        "dest_addr_start": 536870912,
+        "dest_addr_end": 536870924
+      }
+    ]
+  }
+}"#;
+        assert_eq!(render_json(&all).unwrap(), expected);
+    }
+}
+
+// End of file
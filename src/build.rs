@@ -0,0 +1,201 @@
+//! Build Scripts
+//!
+//! Functions for your `build.rs`, which pass some metadata about the build to
+//! your application as environment variables. The [`build_info!`] macro then
+//! turns them into 'Binary Info' entries.
+//!
+//! Add this crate to your `[build-dependencies]` with the `build` feature
+//! enabled, and call [`emit`] from the `main` function in your `build.rs`:
+//!
+//! ```no_run
+//! rp_binary_info::build::emit();
+//! ```
+//!
+//! We do not print any `cargo:rerun-if-...` lines, so Cargo will re-run your
+//! build script whenever any file in your package changes.
+//!
+//! [`build_info!`]: crate::build_info!
+
+use std::process::Command;
+use std::string::{String, ToString};
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::vec::Vec;
+use std::{env, format, println};
+
+/// The output of `git describe`, or the package version if that fails
+pub const ENV_GIT_VERSION: &str = "RP_BINARY_INFO_GIT_VERSION";
+/// The date of the build, in the same format as the C `__DATE__` macro
+pub const ENV_BUILD_DATE: &str = "RP_BINARY_INFO_BUILD_DATE";
+/// Either `Debug` or `Release`
+pub const ENV_PROFILE: &str = "RP_BINARY_INFO_PROFILE";
+/// The target triple (e.g. `thumbv6m-none-eabi`)
+pub const ENV_TARGET: &str = "RP_BINARY_INFO_TARGET";
+/// A comma-separated list of the enabled Cargo features
+pub const ENV_FEATURES: &str = "RP_BINARY_INFO_FEATURES";
+
+/// Metadata about the build, gathered from Cargo and `git`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Metadata {
+    pub git_version: String,
+    pub build_date: String,
+    pub profile: String,
+    pub target: String,
+    pub features: Vec<String>,
+}
+
+impl Metadata {
+    /// Gather the metadata for the package Cargo is currently building.
+    ///
+    /// This must be called from a build script, as it uses the environment
+    /// variables Cargo sets for them.
+    ///
+    /// If `SOURCE_DATE_EPOCH` is set, it is used as the build date, so you
+    /// can make reproducible builds.
+    pub fn from_env() -> Metadata {
+        let git_version = git_describe()
+            .or_else(|| env::var("CARGO_PKG_VERSION").ok())
+            .unwrap_or_default();
+
+        let timestamp = env::var("SOURCE_DATE_EPOCH")
+            .ok()
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or_else(|| {
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|duration| duration.as_secs())
+                    .unwrap_or_default()
+            });
+
+        let profile = match env::var("PROFILE").as_deref() {
+            Ok("release") => "Release",
+            _ => "Debug",
+        };
+
+        let mut features = enabled_features();
+        features.retain(|name| name != "default");
+        features.sort();
+
+        Metadata {
+            git_version,
+            build_date: format_date(timestamp),
+            profile: profile.to_string(),
+            target: env::var("TARGET").unwrap_or_default(),
+            features,
+        }
+    }
+
+    /// Print the `cargo:rustc-env` lines which pass this metadata to the
+    /// package being built.
+    pub fn emit(&self) {
+        println!("cargo:rustc-env={}={}", ENV_GIT_VERSION, self.git_version);
+        println!("cargo:rustc-env={}={}", ENV_BUILD_DATE, self.build_date);
+        println!("cargo:rustc-env={}={}", ENV_PROFILE, self.profile);
+        println!("cargo:rustc-env={}={}", ENV_TARGET, self.target);
+        println!(
+            "cargo:rustc-env={}={}",
+            ENV_FEATURES,
+            self.features.join(",")
+        );
+    }
+}
+
+/// Gather the metadata for the package being built, and pass it on.
+///
+/// Call this from your `build.rs`.
+pub fn emit() {
+    Metadata::from_env().emit();
+}
+
+/// Get the names of the enabled Cargo features.
+///
+/// Cargo 1.80 and later give us the exact names in `CARGO_CFG_FEATURE`.
+/// Older versions only set a `CARGO_FEATURE_<NAME>` variable for each one,
+/// which is upper-cased with `-` replaced by `_`, so the best we can do is
+/// lower-case it again.
+fn enabled_features() -> Vec<String> {
+    if let Ok(list) = env::var("CARGO_CFG_FEATURE") {
+        return list
+            .split(',')
+            .filter(|name| !name.is_empty())
+            .map(|name| name.to_string())
+            .collect();
+    }
+    env::vars()
+        .filter_map(|(key, _)| {
+            key.strip_prefix("CARGO_FEATURE_")
+                .map(|name| name.to_lowercase())
+        })
+        .collect()
+}
+
+/// Ask `git` to describe the package's source tree.
+fn git_describe() -> Option<String> {
+    let mut command = Command::new("git");
+    command.args(["describe", "--always", "--dirty", "--tags"]);
+    if let Ok(dir) = env::var("CARGO_MANIFEST_DIR") {
+        command.current_dir(dir);
+    }
+    let output = command.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let version = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    if version.is_empty() {
+        None
+    } else {
+        Some(version)
+    }
+}
+
+/// Format a UNIX timestamp like the C `__DATE__` macro (e.g. `Oct  5 2021`).
+fn format_date(timestamp: u64) -> String {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+
+    // Convert days since 1970-01-01 into a civil date. See
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let days = (timestamp / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{} {:>2} {}", MONTHS[(month - 1) as usize], day, year)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_date_like_c() {
+        assert_eq!(format_date(0), "Jan  1 1970");
+        assert_eq!(format_date(1_633_392_000), "Oct  5 2021");
+        assert_eq!(format_date(1_703_980_800), "Dec 31 2023");
+        // The time of day is ignored
+        assert_eq!(format_date(1_633_392_000 + 86_399), "Oct  5 2021");
+    }
+
+    #[test]
+    fn format_date_leap_years() {
+        assert_eq!(format_date(1_582_934_400), "Feb 29 2020");
+        assert_eq!(format_date(1_583_020_800), "Mar  1 2020");
+        // Divisible by 400, so a leap year
+        assert_eq!(format_date(951_782_400), "Feb 29 2000");
+        // Divisible by 100 but not 400, so not a leap year
+        assert_eq!(format_date(4_107_456_000), "Feb 28 2100");
+        assert_eq!(format_date(4_107_542_400), "Mar  1 2100");
+    }
+}
+
+// End of file
@@ -0,0 +1,112 @@
+//! Configuration Values
+//!
+//! Types for variables which are pointed to by
+//! [`PtrInt32WithName`](crate::entry::PtrInt32WithName) and
+//! [`PtrStringWithName`](crate::entry::PtrStringWithName) entries, so that
+//! `picotool config` can change their default values in an image without
+//! recompiling it.
+//!
+//! These variables must live in `.data`, so their default values are stored
+//! in Flash and copied to RAM at start-up. The
+//! [`bi_ptr_int32!`](crate::bi_ptr_int32!) and
+//! [`bi_ptr_string!`](crate::bi_ptr_string!) macros do that for you.
+//!
+//! We read the values with volatile reads, as otherwise the compiler could
+//! notice that nothing ever writes to them, and use the default value it saw
+//! at compile-time instead of the one in the image.
+
+use core::cell::UnsafeCell;
+use core::ffi::CStr;
+
+/// A 32-bit signed integer which can be changed in an image after it has
+/// been built.
+#[repr(transparent)]
+pub struct ConfigInt(UnsafeCell<i32>);
+
+/// A null-terminated string of up to `N - 1` bytes, which can be changed in
+/// an image after it has been built.
+#[repr(transparent)]
+pub struct ConfigString<const N: usize>(UnsafeCell<[u8; N]>);
+
+impl ConfigInt {
+    /// Create a new value, with the given default.
+    pub const fn new(default: i32) -> ConfigInt {
+        ConfigInt(UnsafeCell::new(default))
+    }
+
+    /// Get the current value.
+    pub fn get(&self) -> i32 {
+        // Safety: the pointer is valid, and nothing writes to it at run-time
+        unsafe { self.0.get().read_volatile() }
+    }
+
+    /// Get a pointer to the value, for putting in an Entry.
+    pub const fn as_ptr(&self) -> *const i32 {
+        self.0.get()
+    }
+}
+
+impl<const N: usize> ConfigString<N> {
+    /// Create a new value, with the given default.
+    ///
+    /// The default must be shorter than `N` bytes, to leave room for the
+    /// null terminator, and must not contain a null. If it doesn't fit, you
+    /// will get a compile-time error.
+    pub const fn new(default: &str) -> ConfigString<N> {
+        let bytes = default.as_bytes();
+        if bytes.len() >= N {
+            panic!("the default string is too long - it must be shorter than the maximum length");
+        }
+        let mut value = [0u8; N];
+        let mut idx = 0;
+        while idx < bytes.len() {
+            if bytes[idx] == 0 {
+                panic!("the default string must not contain a null");
+            }
+            value[idx] = bytes[idx];
+            idx += 1;
+        }
+        ConfigString(UnsafeCell::new(value))
+    }
+
+    /// Get the current value.
+    ///
+    /// If the value has been patched and has lost its null terminator, you
+    /// get an empty string.
+    pub fn get(&self) -> &CStr {
+        // Read the pointer with a volatile read, so the compiler cannot tell
+        // where it points, and so cannot assume the string still holds its
+        // default value.
+        let ptr = self.0.get();
+        // Safety: the pointer is on our stack, and valid for reads
+        let ptr = unsafe { core::ptr::read_volatile(&ptr) };
+        // Safety: the pointer is valid, and nothing writes to it at run-time
+        let bytes = unsafe { &*ptr };
+        CStr::from_bytes_until_nul(bytes).unwrap_or_default()
+    }
+
+    /// Get a pointer to the value, for putting in an Entry.
+    pub const fn as_ptr(&self) -> *const u8 {
+        self.0.get().cast()
+    }
+
+    /// Get the maximum length of the value, including the null terminator.
+    pub const fn max_len(&self) -> usize {
+        N
+    }
+}
+
+/// Check whether a value is allowed by the limits in a
+/// [`PtrInt32Limits`](crate::entry::PtrInt32Limits) entry - that is, it is
+/// from `min` to `max` inclusive, and fits in `bits` bits as either a signed
+/// or an unsigned integer.
+pub const fn int_allowed(value: i64, min: i32, max: i32, bits: u32) -> bool {
+    if bits == 0 || bits > 32 {
+        return false;
+    }
+    let lowest = -(1i64 << (bits - 1));
+    let highest = (1i64 << bits) - 1;
+    value >= min as i64 && value <= max as i64 && value >= lowest && value <= highest
+}
+
+// End of file
@@ -0,0 +1,279 @@
+//! ELF
+//!
+//! Types and Functions for reading 'Binary Info' out of an ELF file, on the
+//! host.
+//!
+//! Like `picotool`, we load every `PT_LOAD` segment at its physical (load)
+//! address, which gives us the same view of memory as a UF2 file built from
+//! the same ELF. Only 32-bit little-endian ELF files are supported, as that's
+//! all the RP2040 and RP2350 can run.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::parse::{self, BinaryInfo, SparseImage};
+
+/// The magic bytes at the start of every ELF file
+pub const ELF_MAGIC: [u8; 4] = *b"\x7fELF";
+/// The program header type for a loadable segment
+pub const PT_LOAD: u32 = 1;
+
+/// `e_ident[EI_CLASS]` for a 32-bit ELF file
+const ELFCLASS32: u8 = 1;
+/// `e_ident[EI_DATA]` for a little-endian ELF file
+const ELFDATA2LSB: u8 = 1;
+/// The size of a 32-bit ELF file header
+const EHDR_SIZE: usize = 52;
+/// The size of a 32-bit ELF program header
+const PHDR_SIZE: usize = 32;
+
+/// A program header from an ELF file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    pub p_type: u32,
+    /// Where the segment's bytes are in the file
+    pub offset: u32,
+    /// The run-time address of the segment
+    pub vaddr: u32,
+    /// The load address of the segment (e.g. in Flash)
+    pub paddr: u32,
+    /// How many bytes of the segment are in the file
+    pub filesz: u32,
+    /// How many bytes the segment occupies in memory
+    pub memsz: u32,
+}
+
+/// A memory image, loaded from an ELF file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElfImage {
+    /// The address of the entry point
+    pub entry_point: u32,
+    /// Every byte of every `PT_LOAD` segment, at its physical address
+    pub memory: SparseImage,
+}
+
+/// The ways in which reading an ELF file can fail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The file does not start with [`ELF_MAGIC`]
+    NotElf,
+    /// The file is not a 32-bit little-endian ELF file
+    Unsupported,
+    /// The file ends part-way through a header or segment
+    Truncated,
+    /// The file has no loadable segments with any data in them
+    Empty,
+    /// We could not parse the Binary Info in the image
+    Parse(parse::Error),
+}
+
+impl Segment {
+    /// Is this a loadable segment with some data in the file?
+    pub fn is_loaded(&self) -> bool {
+        self.p_type == PT_LOAD && self.filesz != 0
+    }
+}
+
+impl From<parse::Error> for Error {
+    fn from(error: parse::Error) -> Error {
+        Error::Parse(error)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotElf => write!(f, "not an ELF file"),
+            Error::Unsupported => write!(f, "not a 32-bit little-endian ELF file"),
+            Error::Truncated => write!(f, "ELF file is truncated"),
+            Error::Empty => write!(f, "ELF file has no loadable segments"),
+            Error::Parse(error) => error.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Parse(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Read every program header in an ELF file.
+pub fn segments(data: &[u8]) -> Result<Vec<Segment>, Error> {
+    if data.len() < ELF_MAGIC.len() || data[0..4] != ELF_MAGIC {
+        return Err(Error::NotElf);
+    }
+    if data.len() < EHDR_SIZE {
+        return Err(Error::Truncated);
+    }
+    if data[4] != ELFCLASS32 || data[5] != ELFDATA2LSB {
+        return Err(Error::Unsupported);
+    }
+    let phoff = read_u32(data, 0x1C)? as usize;
+    let phentsize = read_u16(data, 0x2A)? as usize;
+    let phnum = read_u16(data, 0x2C)? as usize;
+    if phnum != 0 && phentsize < PHDR_SIZE {
+        return Err(Error::Unsupported);
+    }
+
+    let mut segments = Vec::with_capacity(phnum);
+    for idx in 0..phnum {
+        // On a 32-bit host, a bad header could overflow this
+        let phdr = idx
+            .checked_mul(phentsize)
+            .and_then(|offset| offset.checked_add(phoff))
+            .and_then(|start| data.get(start..))
+            .ok_or(Error::Truncated)?;
+        segments.push(Segment {
+            p_type: read_u32(phdr, 0)?,
+            offset: read_u32(phdr, 4)?,
+            vaddr: read_u32(phdr, 8)?,
+            paddr: read_u32(phdr, 12)?,
+            filesz: read_u32(phdr, 16)?,
+            memsz: read_u32(phdr, 20)?,
+        });
+    }
+    Ok(segments)
+}
+
+/// Load every `PT_LOAD` segment in an ELF file at its physical address.
+pub fn read(data: &[u8]) -> Result<ElfImage, Error> {
+    let mut memory = SparseImage::new();
+    for segment in segments(data)?.iter().filter(|s| s.is_loaded()) {
+        let start = segment.offset as usize;
+        let bytes = start
+            .checked_add(segment.filesz as usize)
+            .and_then(|end| data.get(start..end))
+            .ok_or(Error::Truncated)?;
+        memory.insert(segment.paddr, bytes);
+    }
+    if memory.base_address().is_none() {
+        return Err(Error::Empty);
+    }
+    Ok(ElfImage {
+        entry_point: read_u32(data, 0x18)?,
+        memory,
+    })
+}
+
+/// Parse the 'Binary Info' in an ELF file.
+///
+/// We look for the [`Header`](crate::Header) at the start of the lowest
+/// loaded segment, which is the start of Flash for a normal program, or the
+/// start of RAM for a `no_flash` program.
+pub fn parse(data: &[u8]) -> Result<BinaryInfo, Error> {
+    let image = read(data)?;
+    let base_address = image.memory.base_address().ok_or(Error::Empty)?;
+    Ok(parse::parse_memory(&image.memory, base_address)?)
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, Error> {
+    let bytes = offset
+        .checked_add(2)
+        .and_then(|end| data.get(offset..end))
+        .ok_or(Error::Truncated)?;
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, Error> {
+    let bytes = offset
+        .checked_add(4)
+        .and_then(|end| data.get(offset..end))
+        .ok_or(Error::Truncated)?;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::tests::{sample_image, BASE};
+
+    /// Build an ELF file with one loadable segment holding `image`.
+    fn to_elf(image: &[u8], base_address: u32) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&ELF_MAGIC);
+        data.extend_from_slice(&[ELFCLASS32, ELFDATA2LSB, 1]);
+        data.resize(0x18, 0);
+        let data_offset = (EHDR_SIZE + PHDR_SIZE) as u32;
+        for word in [base_address, EHDR_SIZE as u32, 0, 0] {
+            data.extend_from_slice(&word.to_le_bytes());
+        }
+        for half in [EHDR_SIZE as u16, PHDR_SIZE as u16, 1, 0, 0, 0] {
+            data.extend_from_slice(&half.to_le_bytes());
+        }
+        let len = image.len() as u32;
+        for word in [
+            PT_LOAD,
+            data_offset,
+            base_address,
+            base_address,
+            len,
+            len,
+            0,
+            0,
+        ] {
+            data.extend_from_slice(&word.to_le_bytes());
+        }
+        data.extend_from_slice(image);
+        data
+    }
+
+    #[test]
+    fn parse_loads_segments() {
+        let data = to_elf(&sample_image(), BASE);
+        assert_eq!(
+            segments(&data).unwrap(),
+            [Segment {
+                p_type: PT_LOAD,
+                offset: (EHDR_SIZE + PHDR_SIZE) as u32,
+                vaddr: BASE,
+                paddr: BASE,
+                filesz: sample_image().len() as u32,
+                memsz: sample_image().len() as u32,
+            }]
+        );
+        assert_eq!(read(&data).unwrap().entry_point, BASE);
+        assert_eq!(
+            parse(&data).unwrap(),
+            parse::parse(&sample_image(), BASE).unwrap()
+        );
+    }
+
+    #[test]
+    fn segments_rejects_truncated_program_headers() {
+        let data = to_elf(&sample_image(), BASE);
+        assert_eq!(segments(&data[..EHDR_SIZE + 20]), Err(Error::Truncated));
+        // Claim a second program header, past the end of the file
+        let mut data = to_elf(&[], BASE);
+        data[0x2C] = 2;
+        assert_eq!(segments(&data), Err(Error::Truncated));
+    }
+
+    #[test]
+    fn read_rejects_truncated_segment() {
+        let data = to_elf(&sample_image(), BASE);
+        assert_eq!(read(&data[..data.len() - 1]), Err(Error::Truncated));
+    }
+
+    #[test]
+    fn read_past_the_end_of_memory() {
+        let data = to_elf(&sample_image(), BASE);
+        assert_eq!(read_u16(&data, usize::MAX - 1), Err(Error::Truncated));
+        assert_eq!(read_u32(&data, usize::MAX - 3), Err(Error::Truncated));
+    }
+
+    #[test]
+    fn segments_rejects_other_files() {
+        assert_eq!(segments(b"\x7fEL"), Err(Error::NotElf));
+        assert_eq!(segments(&[0; EHDR_SIZE]), Err(Error::NotElf));
+        let mut data = to_elf(&sample_image(), BASE);
+        data[4] = 2;
+        assert_eq!(segments(&data), Err(Error::Unsupported));
+    }
+}
+
+// End of file
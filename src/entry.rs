@@ -2,13 +2,33 @@
 //! 
 //! Types to describe Entries - the objects which are pointed to from the Entry Table.
 
+use core::ffi::CStr;
+
+use super::DataType;
+
 /// All Entries start with this common header
 #[repr(C)]
-pub(crate) struct Common {
-    pub(crate) data_type: super::DataType,
+pub struct Common {
+    pub(crate) data_type: DataType,
     pub(crate) tag: u16,
 }
 
+/// Implemented by every type which can be put in the Entry Table.
+///
+/// You can implement this for your own types, and then use them with
+/// [`bi_decl!`](crate::bi_decl!) or [`Addr::of`].
+///
+/// # Safety
+///
+/// The type must be `#[repr(C)]`, must start with a [`Common`] header whose
+/// data type is [`Self::DATA_TYPE`], and must otherwise have the layout
+/// `picotool` expects for that data type. Any pointers in it must point to
+/// data which lives forever.
+pub unsafe trait EntryType {
+    /// The data type in this entry's header
+    const DATA_TYPE: DataType;
+}
+
 /// An entry which contains both an ID (e.g. `ID_RP_PROGRAM_NAME`) and a pointer to a null-terminated string.
 #[repr(C)]
 pub struct IdAndString {
@@ -25,22 +45,496 @@ pub struct IdAndInt {
     pub value: u32,
 }
 
+/// An entry which contains both an ID (e.g. `ID_RP_BINARY_END`) and an
+/// address.
+///
+/// We cannot turn an address into an integer at compile time, so this holds
+/// a pointer instead. On the RP2040 and RP2350 a pointer is 32-bits, so this
+/// has exactly the same layout as an [`IdAndInt`], and `picotool` sees it as
+/// one.
+#[repr(C)]
+pub struct IdAndAddress {
+    pub(crate) header: Common,
+    pub id: u32,
+    pub value: *const u8,
+}
+
+/// An entry which describes one or more pins, and the function they have been
+/// assigned to (e.g. UART).
+///
+/// The pins are packed into a single 32-bit value, in the same way as the
+/// `bi_*pins_with_func` macros in the [pico-sdk]:
+///
+/// * bits 0..=2 - the encoding type ([`Self::ENCODING_RANGE`] or [`Self::ENCODING_MULTI`])
+/// * bits 3..=6 - the function (see [`GpioFunction`](super::GpioFunction))
+/// * bits 7.. - the pins, five bits each
+///
+/// With [`Self::ENCODING_MULTI`] there are up to five pins, and the list
+/// ends early if a pin is repeated. With [`Self::ENCODING_RANGE`] the first
+/// pin is the lowest and the second pin is the highest in the range.
+///
+/// [pico-sdk]: https://github.com/raspberrypi/pico-sdk
+#[repr(C)]
+pub struct PinsWithFunction {
+    pub(crate) header: Common,
+    pub pin_encoding: u32,
+}
+
+/// An entry which gives a name to one or more pins.
+///
+/// The pins are given as a bit-mask, and the label is a pointer to a
+/// null-terminated string. If more than one pin is given, the label holds one
+/// name per pin, separated by `|`, in order of increasing pin number.
+#[repr(C)]
+pub struct PinsWithName {
+    pub(crate) header: Common,
+    pub pin_mask: u32,
+    pub label: *const u8,
+}
+
+/// An entry which notes that one or more pins on the RP2350 have been
+/// assigned to a particular function.
+///
+/// The 64-bit pin encoding is stored as two 32-bit words, low word first, so
+/// that the entry has the same packed layout as in the [pico-sdk]:
+///
+/// * bits 0..=2 - the encoding type ([`Self::ENCODING_RANGE`] or [`Self::ENCODING_MULTI`])
+/// * bits 3..=7 - the function (see [`Rp2350GpioFunction`](super::Rp2350GpioFunction))
+/// * bits 8.. - the pins, eight bits each
+///
+/// With [`Self::ENCODING_MULTI`] there are up to seven pins, and the list
+/// ends early if a pin is repeated.
+///
+/// [pico-sdk]: https://github.com/raspberrypi/pico-sdk
+#[repr(C)]
+pub struct Pins64WithFunction {
+    pub(crate) header: Common,
+    pub pin_encoding: [u32; 2],
+}
+
+/// An entry which gives a name to one or more pins on the RP2350.
+///
+/// Like [`PinsWithName`], but the 64-bit pin mask is stored as two 32-bit
+/// words, low word first.
+#[repr(C)]
+pub struct Pins64WithName {
+    pub(crate) header: Common,
+    pub pin_mask: [u32; 2],
+    pub label: *const u8,
+}
+
+/// An entry which starts a new group of entries.
+///
+/// The group itself is identified by `group_tag` and `group_id`, and any
+/// [`IdAndString`] or [`IdAndInt`] entry with that same tag and ID is a
+/// member of the group (see [`NamedGroup::member_string`] and
+/// [`NamedGroup::member_integer`]). The group is shown inside its parent,
+/// which is identified by the tag in the header and `parent_id`.
+#[repr(C)]
+pub struct NamedGroup {
+    pub(crate) header: Common,
+    pub parent_id: u32,
+    pub flags: u16,
+    pub group_tag: u16,
+    pub group_id: u32,
+    pub label: *const u8,
+}
+
+/// An entry which describes a block device (e.g. a filesystem) stored in
+/// Flash.
+///
+/// * `name` - a pointer to a null-terminated string naming the device
+/// * `address` - the start address of the device
+/// * `size` - the size of the device, in bytes
+/// * `extra` - a pointer to an additional entry (usually null)
+/// * `flags` - any of the flags from [`BlockDevice`], OR'd together
+#[repr(C)]
+pub struct BlockDevice {
+    pub(crate) header: Common,
+    pub name: *const u8,
+    pub address: u32,
+    pub size: u32,
+    pub extra: *const u32,
+    pub flags: u16,
+}
+
+/// An entry which points at a named 32-bit signed integer variable, such as
+/// a [`ConfigInt`](crate::config::ConfigInt).
+///
+/// The variable is usually in RAM, so `picotool` uses the Mapping Table to
+/// find its default value in Flash. The `id` is the ID of the group the
+/// variable belongs to, and the label is the variable's name. The fields are
+/// in the same order as the [pico-sdk]'s `binary_info_ptr_int32_with_name_t`.
+///
+/// [pico-sdk]: https://github.com/raspberrypi/pico-sdk
+#[repr(C)]
+pub struct PtrInt32WithName {
+    pub(crate) header: Common,
+    pub id: u32,
+    pub value: *const i32,
+    pub label: *const u8,
+}
+
+/// An entry which points at a named string variable, such as a
+/// [`ConfigString`](crate::config::ConfigString).
+///
+/// Like [`PtrInt32WithName`], but `len` gives the size of the buffer holding
+/// the string, including the null terminator. The fields are in the same
+/// order as the [pico-sdk]'s `binary_info_ptr_string_with_name_t`.
+///
+/// [pico-sdk]: https://github.com/raspberrypi/pico-sdk
+#[repr(C)]
+pub struct PtrStringWithName {
+    pub(crate) header: Common,
+    pub id: u32,
+    pub value: *const u8,
+    pub label: *const u8,
+    pub len: u32,
+}
+
+/// An entry which records the values allowed for the variable that a
+/// [`PtrInt32WithName`] entry points at, so that tools which change it (such
+/// as [`patch`](crate::patch)) can check the new value.
+///
+/// `picotool` has nowhere to store this, so it is a [`SizedData`] entry as
+/// far as `picotool` is concerned. It has the same tag and ID as the
+/// [`PtrInt32WithName`] entry, [`Self::MAGIC`] at the start of its data, and
+/// points at the same variable.
+///
+/// The value must be from `min` to `max` inclusive, and must fit in `bits`
+/// bits as either a signed or an unsigned integer.
+#[repr(C)]
+pub struct PtrInt32Limits {
+    pub(crate) header: Common,
+    pub(crate) length: u32,
+    pub(crate) magic: u32,
+    pub id: u32,
+    pub value: *const i32,
+    pub min: i32,
+    pub max: i32,
+    pub bits: u32,
+}
+
+/// An entry which holds `N` bytes of application-specific data.
+///
+/// The bytes follow the header directly, as in the [pico-sdk]'s
+/// `binary_info_raw_data_t`. Nothing records how many bytes there are, so
+/// whoever reads the entry must already know - use a [`SizedData`] if they
+/// won't.
+///
+/// [pico-sdk]: https://github.com/raspberrypi/pico-sdk
+#[repr(C)]
+pub struct Raw<const N: usize> {
+    pub(crate) header: Common,
+    pub bytes: [u8; N],
+}
+
+/// An entry which holds `N` bytes of application-specific data, along with
+/// their length.
+///
+/// The length and then the bytes follow the header directly, as in the
+/// [pico-sdk]'s `binary_info_sized_data_t`.
+///
+/// [pico-sdk]: https://github.com/raspberrypi/pico-sdk
+#[repr(C)]
+pub struct SizedData<const N: usize> {
+    pub(crate) header: Common,
+    pub length: u32,
+    pub bytes: [u8; N],
+}
+
 /// This is a reference to an entry. It's like a `&dyn` ref to some type `T:
 /// Entry`, except that the run-time type information is encoded into the
 /// Entry itself in very specific way.
 #[repr(transparent)]
 pub struct Addr(*const u32);
 
+impl Common {
+    /// Create a new header, with the given data type and tag.
+    pub const fn new(data_type: DataType, tag: u16) -> Common {
+        Common { data_type, tag }
+    }
+
+    /// Create the header for an entry of type `T`, with the given tag.
+    pub const fn of<T: EntryType>(tag: u16) -> Common {
+        Common {
+            data_type: T::DATA_TYPE,
+            tag,
+        }
+    }
+
+    /// Get the data type
+    pub const fn data_type(&self) -> DataType {
+        self.data_type
+    }
+
+    /// Get the tag
+    pub const fn tag(&self) -> u16 {
+        self.tag
+    }
+}
+
+impl Addr {
+    /// A null address, used to fill arrays before we set them up.
+    pub(crate) const NULL: Addr = Addr(core::ptr::null());
+
+    /// Get the address of an entry, for putting in the Entry Table.
+    ///
+    /// If the data type in the entry's header is not `T::DATA_TYPE`, you
+    /// will get a compile-time error.
+    pub const fn of<T: EntryType>(entry: &'static T) -> Addr {
+        Addr::from_ref(entry)
+    }
+
+    /// As [`Addr::of`], for the `addr` methods on our own entry types, which
+    /// take any reference so as not to break existing callers.
+    const fn from_ref<T: EntryType>(entry: &T) -> Addr {
+        let ptr = entry as *const T;
+        // Safety: implementing `EntryType` promises that `T` starts with a
+        // `Common` header
+        let header = unsafe { &*ptr.cast::<Common>() };
+        if header.data_type as u16 != T::DATA_TYPE as u16 {
+            panic!("the entry's header has the wrong data type");
+        }
+        Addr(ptr.cast())
+    }
+
+    /// Get the address of the entry this refers to.
+    pub(crate) const fn as_ptr(&self) -> *const u32 {
+        self.0
+    }
+}
+
 impl IdAndString {
+    /// An entry with no string, used to fill arrays before we set them up.
+    pub(crate) const EMPTY: IdAndString = IdAndString {
+        header: Common::of::<IdAndString>(0),
+        id: 0,
+        value: core::ptr::null(),
+    };
+
+    /// Create a new entry with the given tag, ID and string.
+    ///
+    /// Using a [`CStr`] means the string is always null-terminated.
+    pub const fn new(tag: u16, id: u32, value: &'static CStr) -> IdAndString {
+        IdAndString {
+            header: Common::of::<IdAndString>(tag),
+            id,
+            value: super::cstr_ptr(value),
+        }
+    }
+
     /// Get this entry's address
     pub const fn addr(&self) -> Addr {
-        Addr(self as *const Self as *const u32)
+        Addr::from_ref(self)
     }
 }
 
 impl IdAndInt {
     /// Get this entry's address
     pub const fn addr(&self) -> Addr {
-        Addr(self as *const Self as *const u32)
+        Addr::from_ref(self)
     }
 }
+
+impl IdAndAddress {
+    /// Get this entry's address
+    pub const fn addr(&self) -> Addr {
+        Addr::from_ref(self)
+    }
+}
+
+impl PinsWithName {
+    /// Get this entry's address
+    pub const fn addr(&self) -> Addr {
+        Addr::from_ref(self)
+    }
+}
+
+impl Pins64WithName {
+    /// Get this entry's address
+    pub const fn addr(&self) -> Addr {
+        Addr::from_ref(self)
+    }
+}
+
+impl PtrInt32WithName {
+    /// Get this entry's address
+    pub const fn addr(&self) -> Addr {
+        Addr::from_ref(self)
+    }
+}
+
+impl PtrStringWithName {
+    /// Get this entry's address
+    pub const fn addr(&self) -> Addr {
+        Addr::from_ref(self)
+    }
+}
+
+impl PtrInt32Limits {
+    /// Marks a [`SizedData`] entry as one of these
+    pub const MAGIC: u32 = u32::from_le_bytes(*b"I32L");
+    /// The length of the data after the `length` field
+    pub const LENGTH: u32 = 24;
+
+    /// Get this entry's address
+    pub const fn addr(&self) -> Addr {
+        Addr::from_ref(self)
+    }
+}
+
+impl<const N: usize> Raw<N> {
+    /// Get this entry's address
+    pub const fn addr(&self) -> Addr {
+        Addr::from_ref(self)
+    }
+}
+
+impl<const N: usize> SizedData<N> {
+    /// Get this entry's address
+    pub const fn addr(&self) -> Addr {
+        Addr::from_ref(self)
+    }
+}
+
+impl NamedGroup {
+    /// Show the group even if it has no members (the default is to hide it)
+    pub const SHOW_IF_EMPTY: u16 = 0x0001;
+    /// Separate the members with commas (the default is newlines)
+    pub const SEPARATE_COMMAS: u16 = 0x0002;
+    /// Sort the members alphabetically (the default is no sorting)
+    pub const SORT_ALPHA: u16 = 0x0004;
+    /// Only show the group in advanced mode (e.g. `picotool info -a`)
+    pub const ADVANCED: u16 = 0x0008;
+
+    /// Create an [`IdAndString`] entry which is a member of this group.
+    ///
+    /// The given string must be null-terminated, so put a `\0` at the end of
+    /// it. If you forget, you will get a compile-time error.
+    pub const fn member_string(&self, value: &'static str) -> IdAndString {
+        super::custom_string(self.group_tag, self.group_id, value)
+    }
+
+    /// Create an [`IdAndString`] entry which is a member of this group, from
+    /// a C string literal.
+    pub const fn member_string_cstr(&self, value: &'static CStr) -> IdAndString {
+        IdAndString::new(self.group_tag, self.group_id, value)
+    }
+
+    /// Create an [`IdAndInt`] entry which is a member of this group.
+    pub const fn member_integer(&self, value: u32) -> IdAndInt {
+        super::custom_integer(self.group_tag, self.group_id, value)
+    }
+
+    /// Get this entry's address
+    pub const fn addr(&self) -> Addr {
+        Addr::from_ref(self)
+    }
+}
+
+impl BlockDevice {
+    /// The device may be read
+    pub const FLAG_READ: u16 = 1 << 0;
+    /// The device may be written
+    pub const FLAG_WRITE: u16 = 1 << 1;
+    /// The device may be reformatted
+    pub const FLAG_REFORMAT: u16 = 1 << 2;
+    /// The partition table type is unknown
+    pub const FLAG_PT_UNKNOWN: u16 = 0 << 4;
+    /// The device has an MBR partition table
+    pub const FLAG_PT_MBR: u16 = 1 << 4;
+    /// The device has a GPT partition table
+    pub const FLAG_PT_GPT: u16 = 2 << 4;
+    /// The device has no partition table
+    pub const FLAG_PT_NONE: u16 = 3 << 4;
+
+    /// Get this entry's address
+    pub const fn addr(&self) -> Addr {
+        Addr::from_ref(self)
+    }
+}
+
+impl PinsWithFunction {
+    /// The pins are a contiguous range, given as the lowest and highest pin
+    pub const ENCODING_RANGE: u32 = 1;
+    /// The pins are a list of up to five pins
+    pub const ENCODING_MULTI: u32 = 2;
+    /// The most pins that fit in a [`Self::ENCODING_MULTI`] entry
+    pub const MAX_PINS: usize = 5;
+
+    /// Get this entry's address
+    pub const fn addr(&self) -> Addr {
+        Addr::from_ref(self)
+    }
+}
+
+impl Pins64WithFunction {
+    /// The pins are a contiguous range, given as the lowest and highest pin
+    pub const ENCODING_RANGE: u64 = 1;
+    /// The pins are a list of up to seven pins
+    pub const ENCODING_MULTI: u64 = 2;
+    /// The most pins that fit in a [`Self::ENCODING_MULTI`] entry
+    pub const MAX_PINS: usize = 7;
+
+    /// Get this entry's address
+    pub const fn addr(&self) -> Addr {
+        Addr::from_ref(self)
+    }
+}
+
+unsafe impl EntryType for PtrInt32Limits {
+    const DATA_TYPE: DataType = DataType::SizedData;
+}
+
+unsafe impl<const N: usize> EntryType for Raw<N> {
+    const DATA_TYPE: DataType = DataType::Raw;
+}
+
+unsafe impl<const N: usize> EntryType for SizedData<N> {
+    const DATA_TYPE: DataType = DataType::SizedData;
+}
+
+unsafe impl EntryType for IdAndString {
+    const DATA_TYPE: DataType = DataType::IdAndString;
+}
+
+unsafe impl EntryType for IdAndInt {
+    const DATA_TYPE: DataType = DataType::IdAndInt;
+}
+
+unsafe impl EntryType for IdAndAddress {
+    const DATA_TYPE: DataType = DataType::IdAndInt;
+}
+
+unsafe impl EntryType for PinsWithFunction {
+    const DATA_TYPE: DataType = DataType::PinsWithFunction;
+}
+
+unsafe impl EntryType for PinsWithName {
+    const DATA_TYPE: DataType = DataType::PinsWithName;
+}
+
+unsafe impl EntryType for Pins64WithFunction {
+    const DATA_TYPE: DataType = DataType::Pins64WithFunction;
+}
+
+unsafe impl EntryType for Pins64WithName {
+    const DATA_TYPE: DataType = DataType::Pins64WithName;
+}
+
+unsafe impl EntryType for NamedGroup {
+    const DATA_TYPE: DataType = DataType::NamedGroup;
+}
+
+unsafe impl EntryType for BlockDevice {
+    const DATA_TYPE: DataType = DataType::BlockDevice;
+}
+
+unsafe impl EntryType for PtrInt32WithName {
+    const DATA_TYPE: DataType = DataType::PtrInt32WithName;
+}
+
+unsafe impl EntryType for PtrStringWithName {
+    const DATA_TYPE: DataType = DataType::PtrStringWithName;
+}
@@ -5,7 +5,28 @@
 
 #![no_std]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+use core::ffi::CStr;
+
+#[cfg(feature = "build")]
+pub mod build;
+pub mod config;
+#[cfg(feature = "elf")]
+pub mod elf;
 pub mod entry;
+pub mod list;
+mod macros;
+#[cfg(feature = "alloc")]
+pub mod parse;
+#[cfg(feature = "alloc")]
+pub mod patch;
+pub mod table;
+#[cfg(feature = "uf2")]
+pub mod uf2;
 
 /// This is the 'Binary Info' header block that `picotool` looks for in your
 /// UF2 file to give you useful metadata about your program. It should be
@@ -36,8 +57,17 @@ pub struct MappingTableEntry {
     pub dest_addr_end: *const u32,
 }
 
+/// A RAM/Flash address mapping table with `N` entries, which always ends with
+/// a [`MappingTableEntry::TERMINATOR`], so `picotool` knows where to stop.
+#[repr(C)]
+pub struct MappingTable<const N: usize> {
+    entries: [MappingTableEntry; N],
+    terminator: MappingTableEntry,
+}
+
 /// This is the set of data types that `picotool` supports.
 #[repr(u16)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum DataType {
     Raw = 1,
     SizedData = 2,
@@ -47,8 +77,74 @@ pub enum DataType {
     IdAndString = 6,
     BlockDevice = 7,
     PinsWithFunction = 8,
+    /// Also used for entries which name several pins at once (which the
+    /// [pico-sdk] calls `PINS_WITH_NAMES`, but gives the same value).
+    ///
+    /// [pico-sdk]: https://github.com/raspberrypi/pico-sdk
     PinsWithName = 9,
-    PinsWithNames = 10,
+    NamedGroup = 10,
+    /// Added for the RP2350
+    PtrInt32WithName = 11,
+    /// Added for the RP2350
+    PtrStringWithName = 12,
+    /// Added for the RP2350, which has more than 32 pins
+    Pins64WithFunction = 13,
+    /// Added for the RP2350, which has more than 32 pins. Also used for
+    /// entries which name several pins at once (`PINS64_WITH_NAMES`).
+    Pins64WithName = 14,
+}
+
+impl DataType {
+    /// The old name for [`DataType::PinsWithName`], which used to have the
+    /// wrong value.
+    #[deprecated(note = "use `DataType::PinsWithName`")]
+    #[allow(non_upper_case_globals)]
+    pub const PinsWithNames: DataType = DataType::PinsWithName;
+}
+
+/// The functions a GPIO pin can be assigned to on the RP2040.
+///
+/// These match the `GPIO_FUNC_*` values in the [pico-sdk] and are used in
+/// [`entry::PinsWithFunction`] entries.
+///
+/// [pico-sdk]: https://github.com/raspberrypi/pico-sdk
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GpioFunction {
+    Xip = 0,
+    Spi = 1,
+    Uart = 2,
+    I2c = 3,
+    Pwm = 4,
+    Sio = 5,
+    Pio0 = 6,
+    Pio1 = 7,
+    Gpck = 8,
+    Usb = 9,
+}
+
+/// The functions a GPIO pin can be assigned to on the RP2350.
+///
+/// These match the `GPIO_FUNC_*` values in the [pico-sdk] and are used in
+/// [`entry::Pins64WithFunction`] entries.
+///
+/// [pico-sdk]: https://github.com/raspberrypi/pico-sdk
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Rp2350GpioFunction {
+    Hstx = 0,
+    Spi = 1,
+    Uart = 2,
+    I2c = 3,
+    Pwm = 4,
+    Sio = 5,
+    Pio0 = 6,
+    Pio1 = 7,
+    Pio2 = 8,
+    /// Also used for `XIP_CS1` and `CORESIGHT_TRACE`, depending on the pin
+    Gpck = 9,
+    Usb = 10,
+    UartAux = 11,
 }
 
 /// All Raspberry Pi specified IDs have this tag. You can create your own
@@ -80,15 +176,16 @@ pub const ID_RP_BOOT2_NAME: u32 = 0x7f8882e1;
 
 impl Header {
     /// This is the `BINARY_INFO_MARKER_START` magic value from `picotool`
-    const MARKER_START: u32 = 0x7188ebf2;
+    pub const MARKER_START: u32 = 0x7188ebf2;
     /// This is the `BINARY_INFO_MARKER_END` magic value from `picotool`
-    const MARKER_END: u32 = 0xe71aa390;
+    pub const MARKER_END: u32 = 0xe71aa390;
 
     /// Create a new `picotool` compatible header.
     ///
     /// * `entries_start` - the first [`entry::Addr`](binary_info::entry::Addr) in the table
     /// * `entries_end` - the last [`entry::Addr`](binary_info::entry::Addr) in the table
-    /// * `mapping_table` - the RAM/Flash address mapping table
+    /// * `mapping_table` - the RAM/Flash address mapping table, which must
+    ///   end with a [`MappingTableEntry::TERMINATOR`] (see [`MappingTable`])
     pub const fn new(
         entries_start: &'static entry::Addr,
         entries_end: &'static entry::Addr,
@@ -103,60 +200,260 @@ impl Header {
             marker_end: Self::MARKER_END,
         }
     }
+
+    /// Iterate over the Entries in the Entry Table this header points to.
+    ///
+    /// Most programs can use [`table::entries`] instead, which doesn't need
+    /// the header.
+    ///
+    /// # Safety
+    ///
+    /// The header must have been created with the start and end of a real
+    /// Entry Table (e.g. by [`binary_info_header!`]), and every
+    /// [`entry::Addr`] in it must point to a valid Entry.
+    pub unsafe fn entries(&self) -> table::Entries {
+        table::Entries::new(self.entries_start, self.entries_end)
+    }
+}
+
+impl MappingTableEntry {
+    /// This all-null entry marks the end of a mapping table
+    pub const TERMINATOR: MappingTableEntry = MappingTableEntry {
+        source_addr_start: core::ptr::null(),
+        dest_addr_start: core::ptr::null(),
+        dest_addr_end: core::ptr::null(),
+    };
+
+    /// Create a new mapping table entry.
+    ///
+    /// * `source_addr_start` - where the data is stored in Flash
+    /// * `dest_addr_start` - where the data is copied to at run-time
+    /// * `dest_addr_end` - the end of the data at run-time
+    pub const fn new(
+        source_addr_start: *const u32,
+        dest_addr_start: *const u32,
+        dest_addr_end: *const u32,
+    ) -> MappingTableEntry {
+        MappingTableEntry {
+            source_addr_start,
+            dest_addr_start,
+            dest_addr_end,
+        }
+    }
+}
+
+impl<const N: usize> MappingTable<N> {
+    /// Create a new mapping table from the given entries. The terminator is
+    /// added for you.
+    pub const fn new(entries: [MappingTableEntry; N]) -> MappingTable<N> {
+        MappingTable {
+            entries,
+            terminator: MappingTableEntry::TERMINATOR,
+        }
+    }
+
+    /// Get the whole table, including the terminator, for passing to
+    /// [`Header::new`].
+    pub const fn as_slice(&self) -> &[MappingTableEntry] {
+        // Safety: we are `repr(C)`, so the terminator comes straight after
+        // the entries, with no padding as they are all the same type. The
+        // pointer comes from the whole table, not just `entries`, so it is
+        // valid for all `N + 1` of them.
+        let start = self as *const Self as *const MappingTableEntry;
+        unsafe { core::slice::from_raw_parts(start, N + 1) }
+    }
 }
 
 /// Create a 'Binary Info' entry containing the program name
 ///
 /// The given string must be null-terminated, so put a `\0` at the end of
-/// it.
+/// it. If you forget, you will get a compile-time error.
+///
+/// ```
+/// static NAME: rp_binary_info::entry::IdAndString = rp_binary_info::program_name("blinky\0");
+/// ```
+///
+/// ```compile_fail
+/// static NAME: rp_binary_info::entry::IdAndString = rp_binary_info::program_name("blinky");
+/// ```
+///
+/// ```compile_fail
+/// static NAME: rp_binary_info::entry::IdAndString = rp_binary_info::program_name("blin\0ky\0");
+/// ```
 pub const fn program_name(name: &'static str) -> entry::IdAndString {
     entry::IdAndString {
-        header: entry::Common {
-            data_type: DataType::IdAndString,
-            tag: TAG_RASPBERRY_PI,
-        },
+        header: entry::Common::of::<entry::IdAndString>(TAG_RASPBERRY_PI),
         id: ID_RP_PROGRAM_NAME,
-        value: name.as_ptr() as *const u8,
+        value: check_cstr(name),
     }
 }
 
+/// Create a 'Binary Info' entry containing the program name, from a C
+/// string literal (e.g. `c"My Program"`).
+pub const fn program_name_cstr(name: &'static CStr) -> entry::IdAndString {
+    entry::IdAndString::new(TAG_RASPBERRY_PI, ID_RP_PROGRAM_NAME, name)
+}
+
 /// Create a 'Binary Info' entry containing the program version.
 ///
 /// The given string must be null-terminated, so put a `\0` at the end of
-/// it.
+/// it. If you forget, you will get a compile-time error.
 pub const fn version(name: &'static str) -> entry::IdAndString {
     entry::IdAndString {
-        header: entry::Common {
-            data_type: DataType::IdAndString,
-            tag: TAG_RASPBERRY_PI,
-        },
+        header: entry::Common::of::<entry::IdAndString>(TAG_RASPBERRY_PI),
         id: ID_RP_PROGRAM_VERSION_STRING,
-        value: name.as_ptr() as *const u8,
+        value: check_cstr(name),
     }
 }
 
+/// Create a 'Binary Info' entry containing the program version, from a C
+/// string literal.
+pub const fn version_cstr(name: &'static CStr) -> entry::IdAndString {
+    entry::IdAndString::new(TAG_RASPBERRY_PI, ID_RP_PROGRAM_VERSION_STRING, name)
+}
+
 /// Create a 'Build Info' entry containing the build date
 ///
 /// The given string must be null-terminated, so put a `\0` at the end of
-/// it.
+/// it. If you forget, you will get a compile-time error.
 pub const fn build_date(name: &'static str) -> entry::IdAndString {
     entry::IdAndString {
-        header: entry::Common {
-            data_type: DataType::IdAndString,
-            tag: TAG_RASPBERRY_PI,
-        },
+        header: entry::Common::of::<entry::IdAndString>(TAG_RASPBERRY_PI),
         id: ID_RP_PROGRAM_BUILD_DATE_STRING,
-        value: name.as_ptr() as *const u8,
+        value: check_cstr(name),
+    }
+}
+
+/// Create a 'Build Info' entry containing the build date, from a C string
+/// literal.
+pub const fn build_date_cstr(name: &'static CStr) -> entry::IdAndString {
+    entry::IdAndString::new(TAG_RASPBERRY_PI, ID_RP_PROGRAM_BUILD_DATE_STRING, name)
+}
+
+/// Create a 'Binary Info' entry containing the URL of the program's web site
+/// (e.g. its git repository).
+///
+/// The given string must be null-terminated, so put a `\0` at the end of
+/// it. If you forget, you will get a compile-time error.
+pub const fn program_url(url: &'static str) -> entry::IdAndString {
+    custom_string(TAG_RASPBERRY_PI, ID_RP_PROGRAM_URL, url)
+}
+
+/// Like [`program_url`], but from a C string literal.
+pub const fn program_url_cstr(url: &'static CStr) -> entry::IdAndString {
+    entry::IdAndString::new(TAG_RASPBERRY_PI, ID_RP_PROGRAM_URL, url)
+}
+
+/// Create a 'Binary Info' entry containing the description of the program.
+///
+/// The given string must be null-terminated, so put a `\0` at the end of
+/// it. If you forget, you will get a compile-time error.
+pub const fn program_description(description: &'static str) -> entry::IdAndString {
+    custom_string(TAG_RASPBERRY_PI, ID_RP_PROGRAM_DESCRIPTION, description)
+}
+
+/// Like [`program_description`], but from a C string literal.
+pub const fn program_description_cstr(description: &'static CStr) -> entry::IdAndString {
+    entry::IdAndString::new(TAG_RASPBERRY_PI, ID_RP_PROGRAM_DESCRIPTION, description)
+}
+
+/// Create a 'Binary Info' entry containing a feature of the program (e.g.
+/// "UART stdin / stdout").
+///
+/// You can have as many of these as you like.
+///
+/// The given string must be null-terminated, so put a `\0` at the end of
+/// it. If you forget, you will get a compile-time error.
+pub const fn program_feature(feature: &'static str) -> entry::IdAndString {
+    custom_string(TAG_RASPBERRY_PI, ID_RP_PROGRAM_FEATURE, feature)
+}
+
+/// Like [`program_feature`], but from a C string literal.
+pub const fn program_feature_cstr(feature: &'static CStr) -> entry::IdAndString {
+    entry::IdAndString::new(TAG_RASPBERRY_PI, ID_RP_PROGRAM_FEATURE, feature)
+}
+
+/// Create a 'Binary Info' entry containing a build attribute (e.g. "Debug"
+/// or "Release").
+///
+/// You can have as many of these as you like.
+///
+/// The given string must be null-terminated, so put a `\0` at the end of
+/// it. If you forget, you will get a compile-time error.
+pub const fn build_attribute(attribute: &'static str) -> entry::IdAndString {
+    custom_string(TAG_RASPBERRY_PI, ID_RP_PROGRAM_BUILD_ATTRIBUTE, attribute)
+}
+
+/// Like [`build_attribute`], but from a C string literal.
+pub const fn build_attribute_cstr(attribute: &'static CStr) -> entry::IdAndString {
+    entry::IdAndString::new(TAG_RASPBERRY_PI, ID_RP_PROGRAM_BUILD_ATTRIBUTE, attribute)
+}
+
+/// Create a 'Binary Info' entry containing the version of the SDK (or HAL) the
+/// program was built with.
+///
+/// The given string must be null-terminated, so put a `\0` at the end of
+/// it. If you forget, you will get a compile-time error.
+pub const fn sdk_version(version: &'static str) -> entry::IdAndString {
+    custom_string(TAG_RASPBERRY_PI, ID_RP_SDK_VERSION, version)
+}
+
+/// Like [`sdk_version`], but from a C string literal.
+pub const fn sdk_version_cstr(version: &'static CStr) -> entry::IdAndString {
+    entry::IdAndString::new(TAG_RASPBERRY_PI, ID_RP_SDK_VERSION, version)
+}
+
+/// Create a 'Binary Info' entry containing the board the program was built
+/// for (e.g. "pico").
+///
+/// The given string must be null-terminated, so put a `\0` at the end of
+/// it. If you forget, you will get a compile-time error.
+pub const fn pico_board(board: &'static str) -> entry::IdAndString {
+    custom_string(TAG_RASPBERRY_PI, ID_RP_PICO_BOARD, board)
+}
+
+/// Like [`pico_board`], but from a C string literal.
+pub const fn pico_board_cstr(board: &'static CStr) -> entry::IdAndString {
+    entry::IdAndString::new(TAG_RASPBERRY_PI, ID_RP_PICO_BOARD, board)
+}
+
+/// Create a 'Binary Info' entry containing the name of the `boot2` image the
+/// program uses (e.g. "boot2_w25q080").
+///
+/// The given string must be null-terminated, so put a `\0` at the end of
+/// it. If you forget, you will get a compile-time error.
+pub const fn boot2_name(name: &'static str) -> entry::IdAndString {
+    custom_string(TAG_RASPBERRY_PI, ID_RP_BOOT2_NAME, name)
+}
+
+/// Like [`boot2_name`], but from a C string literal.
+pub const fn boot2_name_cstr(name: &'static CStr) -> entry::IdAndString {
+    entry::IdAndString::new(TAG_RASPBERRY_PI, ID_RP_BOOT2_NAME, name)
+}
+
+/// Create a 'Binary Info' entry containing the address of the end of the
+/// binary, so tools like `picotool save` know how much Flash to read.
+pub const fn binary_end(address: u32) -> entry::IdAndInt {
+    custom_integer(TAG_RASPBERRY_PI, ID_RP_BINARY_END, address)
+}
+
+/// Create a 'Binary Info' entry containing the address of the end of the
+/// binary, given as a pointer.
+///
+/// This lets you use the address of a linker symbol - see
+/// [`binary_end!`](crate::binary_end!) for an easy way to do that.
+pub const fn binary_end_address(address: *const u8) -> entry::IdAndAddress {
+    entry::IdAndAddress {
+        header: entry::Common::of::<entry::IdAndAddress>(TAG_RASPBERRY_PI),
+        id: ID_RP_BINARY_END,
+        value: address,
     }
 }
 
 /// Create a 'Binary Info' entry containing a custom integer entry.
 pub const fn custom_integer(tag: u16, id: u32, value: u32) -> entry::IdAndInt {
     entry::IdAndInt {
-        header: entry::Common {
-            data_type: DataType::IdAndInt,
-            tag,
-        },
+        header: entry::Common::of::<entry::IdAndInt>(tag),
         id,
         value,
     }
@@ -165,41 +462,685 @@ pub const fn custom_integer(tag: u16, id: u32, value: u32) -> entry::IdAndInt {
 /// Create a 'Binary Info' entry containing a custom string entry.
 ///
 /// The given string must be null-terminated, so put a `\0` at the end of
-/// it.
+/// it. If you forget, you will get a compile-time error.
 pub const fn custom_string(tag: u16, id: u32, value: &'static str) -> entry::IdAndString {
     entry::IdAndString {
-        header: entry::Common {
-            data_type: DataType::IdAndString,
-            tag,
-        },
+        header: entry::Common::of::<entry::IdAndString>(tag),
+        id,
+        value: check_cstr(value),
+    }
+}
+
+/// Create a 'Binary Info' entry containing a custom string entry, from a C
+/// string literal.
+pub const fn custom_string_cstr(tag: u16, id: u32, value: &'static CStr) -> entry::IdAndString {
+    entry::IdAndString::new(tag, id, value)
+}
+
+/// Create a 'Binary Info' entry containing some raw bytes (e.g. a packed
+/// struct).
+///
+/// `N` must be the length of `bytes`. If it isn't, you will get a
+/// compile-time error. The entry does not record its length, so whatever
+/// reads it must know what to expect - otherwise use [`sized_data`].
+pub const fn raw_data<const N: usize>(tag: u16, bytes: &[u8]) -> entry::Raw<N> {
+    entry::Raw {
+        header: entry::Common::of::<entry::Raw<N>>(tag),
+        bytes: copy_bytes(bytes),
+    }
+}
+
+/// Create a 'Binary Info' entry containing some bytes, and their length.
+///
+/// `N` must be the length of `bytes`. If it isn't, you will get a
+/// compile-time error.
+pub const fn sized_data<const N: usize>(tag: u16, bytes: &[u8]) -> entry::SizedData<N> {
+    entry::SizedData {
+        header: entry::Common::of::<entry::SizedData<N>>(tag),
+        length: N as u32,
+        bytes: copy_bytes(bytes),
+    }
+}
+
+/// Create a 'Binary Info' entry describing a block device (e.g. a filesystem)
+/// stored in Flash.
+///
+/// * `tag` - the tag to use for this entry (e.g. `TAG_RASPBERRY_PI`)
+/// * `name` - the name of the device
+/// * `address` - the start address of the device
+/// * `size` - the size of the device, in bytes
+/// * `flags` - any of the flags from [`entry::BlockDevice`], OR'd together
+///
+/// The given string must be null-terminated, so put a `\0` at the end of
+/// it. If you forget, you will get a compile-time error.
+pub const fn block_device(
+    tag: u16,
+    name: &'static str,
+    address: u32,
+    size: u32,
+    flags: u16,
+) -> entry::BlockDevice {
+    entry::BlockDevice {
+        header: entry::Common::of::<entry::BlockDevice>(tag),
+        name: check_cstr(name),
+        address,
+        size,
+        extra: core::ptr::null(),
+        flags,
+    }
+}
+
+/// Create a 'Binary Info' entry describing a block device, with a name
+/// from a C string literal.
+///
+/// See [`block_device`] for details of the arguments.
+pub const fn block_device_cstr(
+    tag: u16,
+    name: &'static CStr,
+    address: u32,
+    size: u32,
+    flags: u16,
+) -> entry::BlockDevice {
+    entry::BlockDevice {
+        header: entry::Common::of::<entry::BlockDevice>(tag),
+        name: cstr_ptr(name),
+        address,
+        size,
+        extra: core::ptr::null(),
+        flags,
+    }
+}
+
+/// Create a 'Binary Info' entry which starts a new group of entries.
+///
+/// * `parent_tag` and `parent_id` - identify the group this group belongs in
+/// * `group_tag` and `group_id` - identify this group, and are the tag and ID
+///   used by all of its members
+/// * `label` - the name of this group
+/// * `flags` - any of the flags from [`entry::NamedGroup`], OR'd together
+///
+/// The given string must be null-terminated, so put a `\0` at the end of
+/// it. If you forget, you will get a compile-time error.
+pub const fn named_group(
+    parent_tag: u16,
+    parent_id: u32,
+    group_tag: u16,
+    group_id: u32,
+    label: &'static str,
+    flags: u16,
+) -> entry::NamedGroup {
+    entry::NamedGroup {
+        header: entry::Common::of::<entry::NamedGroup>(parent_tag),
+        parent_id,
+        flags,
+        group_tag,
+        group_id,
+        label: check_cstr(label),
+    }
+}
+
+/// Create a 'Binary Info' entry which starts a new group of entries, with a
+/// label from a C string literal.
+///
+/// See [`named_group`] for details of the arguments.
+pub const fn named_group_cstr(
+    parent_tag: u16,
+    parent_id: u32,
+    group_tag: u16,
+    group_id: u32,
+    label: &'static CStr,
+    flags: u16,
+) -> entry::NamedGroup {
+    entry::NamedGroup {
+        header: entry::Common::of::<entry::NamedGroup>(parent_tag),
+        parent_id,
+        flags,
+        group_tag,
+        group_id,
+        label: cstr_ptr(label),
+    }
+}
+
+/// Create a 'Binary Info' entry which starts a new group of entries, shown
+/// with the program's features.
+///
+/// The given string must be null-terminated, so put a `\0` at the end of
+/// it. If you forget, you will get a compile-time error.
+pub const fn program_feature_group(
+    group_tag: u16,
+    group_id: u32,
+    label: &'static str,
+    flags: u16,
+) -> entry::NamedGroup {
+    named_group(
+        TAG_RASPBERRY_PI,
+        ID_RP_PROGRAM_FEATURE,
+        group_tag,
+        group_id,
+        label,
+        flags,
+    )
+}
+
+/// Create a 'Binary Info' entry which starts a new group of entries, shown
+/// with the program's features, with a label from a C string literal.
+pub const fn program_feature_group_cstr(
+    group_tag: u16,
+    group_id: u32,
+    label: &'static CStr,
+    flags: u16,
+) -> entry::NamedGroup {
+    named_group_cstr(
+        TAG_RASPBERRY_PI,
+        ID_RP_PROGRAM_FEATURE,
+        group_tag,
+        group_id,
+        label,
+        flags,
+    )
+}
+
+/// Create a 'Binary Info' entry which points at a named integer variable.
+///
+/// * `tag` and `id` - identify the group the variable belongs to
+/// * `value` - the variable, which should be in `.data`
+/// * `label` - the name of the variable
+///
+/// The [`bi_ptr_int32!`] macro declares the variable and the entry together.
+///
+/// The given string must be null-terminated, so put a `\0` at the end of
+/// it. If you forget, you will get a compile-time error.
+pub const fn ptr_int32_with_name(
+    tag: u16,
+    id: u32,
+    value: &'static config::ConfigInt,
+    label: &'static str,
+) -> entry::PtrInt32WithName {
+    entry::PtrInt32WithName {
+        header: entry::Common::of::<entry::PtrInt32WithName>(tag),
         id,
-        value: value.as_ptr() as *const u8,
+        value: value.as_ptr(),
+        label: check_cstr(label),
+    }
+}
+
+/// Create a 'Binary Info' entry which points at a named integer variable,
+/// with a C string literal for the name (e.g. `c"UART_BAUD"`).
+pub const fn ptr_int32_with_name_cstr(
+    tag: u16,
+    id: u32,
+    value: &'static config::ConfigInt,
+    label: &'static CStr,
+) -> entry::PtrInt32WithName {
+    entry::PtrInt32WithName {
+        header: entry::Common::of::<entry::PtrInt32WithName>(tag),
+        id,
+        value: value.as_ptr(),
+        label: cstr_ptr(label),
+    }
+}
+
+/// Create a 'Binary Info' entry recording the values allowed for a named
+/// integer variable.
+///
+/// * `tag` and `id` - the same as for the variable's [`ptr_int32_with_name`]
+///   entry
+/// * `value` - the variable
+/// * `min` and `max` - the smallest and largest values allowed
+/// * `bits` - the number of bits the value must fit in, from 1 to 32
+///
+/// The [`bi_ptr_int32!`] macro declares this for you, and checks the
+/// variable's default value is allowed.
+///
+/// ```
+/// use rp_binary_info::{config::ConfigInt, entry::PtrInt32Limits, ptr_int32_limits};
+/// static BAUD: ConfigInt = ConfigInt::new(115200);
+/// static LIMITS: PtrInt32Limits = ptr_int32_limits(0, 0, &BAUD, 9600, 921600, 32);
+/// ```
+///
+/// A `bits` of zero, or a `min` more than `max`, is a compile-time error:
+///
+/// ```compile_fail
+/// use rp_binary_info::{config::ConfigInt, entry::PtrInt32Limits, ptr_int32_limits};
+/// static BAUD: ConfigInt = ConfigInt::new(115200);
+/// static LIMITS: PtrInt32Limits = ptr_int32_limits(0, 0, &BAUD, 9600, 921600, 0);
+/// ```
+///
+/// ```compile_fail
+/// use rp_binary_info::{config::ConfigInt, entry::PtrInt32Limits, ptr_int32_limits};
+/// static BAUD: ConfigInt = ConfigInt::new(115200);
+/// static LIMITS: PtrInt32Limits = ptr_int32_limits(0, 0, &BAUD, 921600, 9600, 32);
+/// ```
+pub const fn ptr_int32_limits(
+    tag: u16,
+    id: u32,
+    value: &'static config::ConfigInt,
+    min: i32,
+    max: i32,
+    bits: u32,
+) -> entry::PtrInt32Limits {
+    if bits == 0 || bits > 32 {
+        panic!("the number of bits must be from 1 to 32");
+    }
+    if min > max {
+        panic!("the minimum must not be more than the maximum");
+    }
+    entry::PtrInt32Limits {
+        header: entry::Common::of::<entry::PtrInt32Limits>(tag),
+        length: entry::PtrInt32Limits::LENGTH,
+        magic: entry::PtrInt32Limits::MAGIC,
+        id,
+        value: value.as_ptr(),
+        min,
+        max,
+        bits,
+    }
+}
+
+/// Create a 'Binary Info' entry which points at a named string variable.
+///
+/// * `tag` and `id` - identify the group the variable belongs to
+/// * `value` - the variable, which should be in `.data`
+/// * `label` - the name of the variable
+///
+/// The [`bi_ptr_string!`] macro declares the variable and the entry together.
+///
+/// The given string must be null-terminated, so put a `\0` at the end of
+/// it. If you forget, you will get a compile-time error.
+pub const fn ptr_string_with_name<const N: usize>(
+    tag: u16,
+    id: u32,
+    value: &'static config::ConfigString<N>,
+    label: &'static str,
+) -> entry::PtrStringWithName {
+    entry::PtrStringWithName {
+        header: entry::Common::of::<entry::PtrStringWithName>(tag),
+        id,
+        value: value.as_ptr(),
+        label: check_cstr(label),
+        len: N as u32,
+    }
+}
+
+/// Create a 'Binary Info' entry which points at a named string variable,
+/// with a C string literal for the name (e.g. `c"DEVICE_NAME"`).
+pub const fn ptr_string_with_name_cstr<const N: usize>(
+    tag: u16,
+    id: u32,
+    value: &'static config::ConfigString<N>,
+    label: &'static CStr,
+) -> entry::PtrStringWithName {
+    entry::PtrStringWithName {
+        header: entry::Common::of::<entry::PtrStringWithName>(tag),
+        id,
+        value: value.as_ptr(),
+        label: cstr_ptr(label),
+        len: N as u32,
+    }
+}
+
+/// Create a 'Binary Info' entry noting that one or more pins have been
+/// assigned to the given function.
+///
+/// You can give up to five pins, each of which must be in the range `0..=29`
+/// and must not be repeated. If you have more pins than that, either use
+/// [`pin_range_with_function`] or create more than one entry.
+///
+/// ```
+/// use rp_binary_info::{entry::PinsWithFunction, pins_with_function, GpioFunction};
+/// static PINS: PinsWithFunction = pins_with_function(&[0, 1, 2, 3, 4], GpioFunction::Spi);
+/// ```
+///
+/// Giving too many pins, or a pin more than once, is a compile-time error:
+///
+/// ```compile_fail
+/// use rp_binary_info::{entry::PinsWithFunction, pins_with_function, GpioFunction};
+/// static PINS: PinsWithFunction = pins_with_function(&[0, 1, 2, 3, 4, 5], GpioFunction::Spi);
+/// ```
+///
+/// ```compile_fail
+/// use rp_binary_info::{entry::PinsWithFunction, pins_with_function, GpioFunction};
+/// static PINS: PinsWithFunction = pins_with_function(&[0, 1, 2, 3, 0], GpioFunction::Spi);
+/// ```
+pub const fn pins_with_function(pins: &[u8], function: GpioFunction) -> entry::PinsWithFunction {
+    if pins.is_empty() || pins.len() > entry::PinsWithFunction::MAX_PINS {
+        panic!("between one and five pins must be given");
+    }
+    let mut pin_encoding = entry::PinsWithFunction::ENCODING_MULTI | ((function as u32) << 3);
+    let mut idx = 0;
+    while idx < pins.len() {
+        let pin = check_pin(pins[idx]);
+        check_not_repeated(pins, idx);
+        pin_encoding |= (pin as u32) << (7 + (idx * 5));
+        idx += 1;
+    }
+    // A repeated pin marks the end of a short list
+    if idx < entry::PinsWithFunction::MAX_PINS {
+        pin_encoding |= (pins[idx - 1] as u32) << (7 + (idx * 5));
+    }
+    entry::PinsWithFunction {
+        header: entry::Common::of::<entry::PinsWithFunction>(TAG_RASPBERRY_PI),
+        pin_encoding,
+    }
+}
+
+/// Create a 'Binary Info' entry noting that a contiguous range of pins,
+/// from `first` to `last` inclusive, have been assigned to the given function.
+pub const fn pin_range_with_function(
+    first: u8,
+    last: u8,
+    function: GpioFunction,
+) -> entry::PinsWithFunction {
+    if check_pin(first) > check_pin(last) {
+        panic!("the first pin in a range must not be after the last pin");
+    }
+    entry::PinsWithFunction {
+        header: entry::Common::of::<entry::PinsWithFunction>(TAG_RASPBERRY_PI),
+        pin_encoding: entry::PinsWithFunction::ENCODING_RANGE
+            | ((function as u32) << 3)
+            | ((first as u32) << 7)
+            | ((last as u32) << 12),
+    }
+}
+
+/// Create a 'Binary Info' entry giving a name to a pin (e.g. "LED").
+///
+/// The given string must be null-terminated, so put a `\0` at the end of
+/// it. If you forget, you will get a compile-time error.
+pub const fn pin_with_name(pin: u8, name: &'static str) -> entry::PinsWithName {
+    entry::PinsWithName {
+        header: entry::Common::of::<entry::PinsWithName>(TAG_RASPBERRY_PI),
+        pin_mask: 1 << check_pin(pin),
+        label: check_cstr(name),
+    }
+}
+
+/// Create a 'Binary Info' entry giving a name to a pin, from a C string
+/// literal (e.g. `c"LED"`).
+pub const fn pin_with_name_cstr(pin: u8, name: &'static CStr) -> entry::PinsWithName {
+    entry::PinsWithName {
+        header: entry::Common::of::<entry::PinsWithName>(TAG_RASPBERRY_PI),
+        pin_mask: 1 << check_pin(pin),
+        label: cstr_ptr(name),
+    }
+}
+
+/// Create a 'Binary Info' entry giving names to several pins.
+///
+/// The pins must be given in increasing order, and `names` must contain one
+/// name for each pin, separated by `|` (e.g. `"SDA|SCL\0"`).
+///
+/// The given string must be null-terminated, so put a `\0` at the end of
+/// it. If you forget, you will get a compile-time error.
+pub const fn pins_with_names(pins: &[u8], names: &'static str) -> entry::PinsWithName {
+    let label = check_cstr(names);
+    entry::PinsWithName {
+        header: entry::Common::of::<entry::PinsWithName>(TAG_RASPBERRY_PI),
+        pin_mask: pin_mask_with_names(pins, names.as_bytes()),
+        label,
+    }
+}
+
+/// Create a 'Binary Info' entry giving names to several pins, from a C
+/// string literal (e.g. `c"SDA|SCL"`).
+///
+/// The pins must be given in increasing order, and `names` must contain one
+/// name for each pin, separated by `|`.
+pub const fn pins_with_names_cstr(pins: &[u8], names: &'static CStr) -> entry::PinsWithName {
+    entry::PinsWithName {
+        header: entry::Common::of::<entry::PinsWithName>(TAG_RASPBERRY_PI),
+        pin_mask: pin_mask_with_names(pins, names.to_bytes()),
+        label: cstr_ptr(names),
     }
 }
 
+/// Create a 'Binary Info' entry noting that one or more pins on the RP2350
+/// have been assigned to the given function.
+///
+/// You can give up to seven pins, each of which must be in the range
+/// `0..=47` and must not be repeated. If you have more pins than that, either
+/// use [`pin64_range_with_function`] or create more than one entry.
+///
+/// ```
+/// use rp_binary_info::{entry::Pins64WithFunction, pins64_with_function, Rp2350GpioFunction};
+/// static PINS: Pins64WithFunction =
+///     pins64_with_function(&[30, 31, 32, 33, 34, 35, 47], Rp2350GpioFunction::Sio);
+/// ```
+///
+/// Giving too many pins, or a pin the RP2350 doesn't have, is a compile-time
+/// error:
+///
+/// ```compile_fail
+/// use rp_binary_info::{entry::Pins64WithFunction, pins64_with_function, Rp2350GpioFunction};
+/// static PINS: Pins64WithFunction =
+///     pins64_with_function(&[30, 31, 32, 33, 34, 35, 36, 37], Rp2350GpioFunction::Sio);
+/// ```
+///
+/// ```compile_fail
+/// use rp_binary_info::{entry::Pins64WithFunction, pins64_with_function, Rp2350GpioFunction};
+/// static PINS: Pins64WithFunction = pins64_with_function(&[46, 48], Rp2350GpioFunction::Sio);
+/// ```
+pub const fn pins64_with_function(
+    pins: &[u8],
+    function: Rp2350GpioFunction,
+) -> entry::Pins64WithFunction {
+    if pins.is_empty() || pins.len() > entry::Pins64WithFunction::MAX_PINS {
+        panic!("between one and seven pins must be given");
+    }
+    let mut pin_encoding = entry::Pins64WithFunction::ENCODING_MULTI | ((function as u64) << 3);
+    let mut idx = 0;
+    while idx < pins.len() {
+        let pin = check_pin64(pins[idx]);
+        check_not_repeated(pins, idx);
+        pin_encoding |= (pin as u64) << (8 + (idx * 8));
+        idx += 1;
+    }
+    // A repeated pin marks the end of a short list
+    if idx < entry::Pins64WithFunction::MAX_PINS {
+        pin_encoding |= (pins[idx - 1] as u64) << (8 + (idx * 8));
+    }
+    entry::Pins64WithFunction {
+        header: entry::Common::of::<entry::Pins64WithFunction>(TAG_RASPBERRY_PI),
+        pin_encoding: split_u64(pin_encoding),
+    }
+}
+
+/// Create a 'Binary Info' entry noting that a contiguous range of pins on
+/// the RP2350, from `first` to `last` inclusive, have been assigned to the
+/// given function.
+pub const fn pin64_range_with_function(
+    first: u8,
+    last: u8,
+    function: Rp2350GpioFunction,
+) -> entry::Pins64WithFunction {
+    if check_pin64(first) > check_pin64(last) {
+        panic!("the first pin in a range must not be after the last pin");
+    }
+    entry::Pins64WithFunction {
+        header: entry::Common::of::<entry::Pins64WithFunction>(TAG_RASPBERRY_PI),
+        pin_encoding: split_u64(
+            entry::Pins64WithFunction::ENCODING_RANGE
+                | ((function as u64) << 3)
+                | ((first as u64) << 8)
+                | ((last as u64) << 16),
+        ),
+    }
+}
+
+/// Create a 'Binary Info' entry giving names to one or more pins on the
+/// RP2350.
+///
+/// The pins must be given in increasing order, and `names` must contain one
+/// name for each pin, separated by `|` (e.g. `"SDA|SCL\0"`).
+///
+/// The given string must be null-terminated, so put a `\0` at the end of
+/// it. If you forget, you will get a compile-time error.
+pub const fn pins64_with_names(pins: &[u8], names: &'static str) -> entry::Pins64WithName {
+    let label = check_cstr(names);
+    entry::Pins64WithName {
+        header: entry::Common::of::<entry::Pins64WithName>(TAG_RASPBERRY_PI),
+        pin_mask: split_u64(pin_mask64_with_names(pins, names.as_bytes())),
+        label,
+    }
+}
+
+/// Create a 'Binary Info' entry giving names to one or more pins on the
+/// RP2350, from a C string literal (e.g. `c"SDA|SCL"`).
+///
+/// The pins must be given in increasing order, and `names` must contain one
+/// name for each pin, separated by `|`.
+pub const fn pins64_with_names_cstr(pins: &[u8], names: &'static CStr) -> entry::Pins64WithName {
+    entry::Pins64WithName {
+        header: entry::Common::of::<entry::Pins64WithName>(TAG_RASPBERRY_PI),
+        pin_mask: split_u64(pin_mask64_with_names(pins, names.to_bytes())),
+        label: cstr_ptr(names),
+    }
+}
+
+/// Check there is one `|` separated name for each pin, and make a mask of
+/// the pins.
+const fn pin_mask_with_names(pins: &[u8], names: &[u8]) -> u32 {
+    let mut idx = 0;
+    while idx < pins.len() {
+        check_pin(pins[idx]);
+        idx += 1;
+    }
+    pin_mask64_with_names(pins, names) as u32
+}
+
+/// Check there is one `|` separated name for each pin, and make a 64-bit
+/// mask of the pins.
+const fn pin_mask64_with_names(pins: &[u8], names: &[u8]) -> u64 {
+    if pins.is_empty() {
+        panic!("at least one pin must be given");
+    }
+    let mut pin_mask = 0;
+    let mut idx = 0;
+    while idx < pins.len() {
+        let pin = check_pin64(pins[idx]);
+        if idx > 0 && pins[idx - 1] >= pin {
+            panic!("pins must be given in increasing order");
+        }
+        pin_mask |= 1 << pin;
+        idx += 1;
+    }
+    let mut num_names = 1;
+    let mut idx = 0;
+    while idx < names.len() {
+        if names[idx] == b'|' {
+            num_names += 1;
+        }
+        idx += 1;
+    }
+    if num_names != pins.len() {
+        panic!("there must be one name for each pin");
+    }
+    pin_mask
+}
+
+/// Check a string is null-terminated, with no other nulls in it, and get a
+/// pointer to it.
+///
+/// Our constructors are used to initialise `static` values, so they are
+/// evaluated at compile-time, and panicking here gives a compile error
+/// rather than an Entry which makes `picotool` read garbage.
+pub(crate) const fn check_cstr(value: &'static str) -> *const u8 {
+    let bytes = value.as_bytes();
+    if bytes.is_empty() || bytes[bytes.len() - 1] != 0 {
+        panic!("string must be null-terminated - put a `\\0` at the end of it");
+    }
+    let mut idx = 0;
+    while idx < bytes.len() - 1 {
+        if bytes[idx] == 0 {
+            panic!("string must not contain a null before the end");
+        }
+        idx += 1;
+    }
+    value.as_ptr()
+}
+
+/// Get a pointer to a C string, as stored in our Entries.
+pub(crate) const fn cstr_ptr(value: &'static CStr) -> *const u8 {
+    value.as_ptr().cast()
+}
+
+/// Copy some bytes into an array of exactly the same length.
+const fn copy_bytes<const N: usize>(bytes: &[u8]) -> [u8; N] {
+    if bytes.len() != N {
+        panic!("the data must be exactly as long as the entry");
+    }
+    let mut output = [0u8; N];
+    let mut idx = 0;
+    while idx < N {
+        output[idx] = bytes[idx];
+        idx += 1;
+    }
+    output
+}
+
+/// Check the pin at `idx` does not appear earlier in the list.
+const fn check_not_repeated(pins: &[u8], idx: usize) {
+    let mut earlier = 0;
+    while earlier < idx {
+        if pins[earlier] == pins[idx] {
+            panic!("pins must not be repeated");
+        }
+        earlier += 1;
+    }
+}
+
+/// Check a pin number is one the RP2040 actually has.
+const fn check_pin(pin: u8) -> u8 {
+    if pin > 29 {
+        panic!("the RP2040 only has pins 0 to 29");
+    }
+    pin
+}
+
+/// Check a pin number is one the RP2350 actually has.
+const fn check_pin64(pin: u8) -> u8 {
+    if pin > 47 {
+        panic!("the RP2350 only has pins 0 to 47");
+    }
+    pin
+}
+
+/// Split a 64-bit value into two 32-bit words, low word first, as our
+/// Entries only need 32-bit alignment.
+const fn split_u64(value: u64) -> [u32; 2] {
+    [value as u32, (value >> 32) as u32]
+}
+
 /// Create a tag from two ASCII letters.
 pub const fn make_tag(c1: u8, c2: u8) -> u16 {
     u16::from_be_bytes([c2, c1])
 }
 
-// We need this as rustc complains that is is unsafe to share `*const u32`
-// pointers between threads. We only allow these to be created with static
-// data, so this is OK.
-unsafe impl Sync for Header {}
-
-// We need this as rustc complains that is is unsafe to share `*const u8`
-// pointers between threads. We only allow these to be created with static
-// string slices, so it's OK.
-unsafe impl Sync for entry::IdAndString {}
+// We need these as rustc complains that is is unsafe to share raw pointers
+// (and `UnsafeCell`s) between threads. We only allow these to be created with
+// static data, which nothing writes to at run-time, so it's OK.
+macro_rules! impl_sync {
+    ($($ty:ty),* $(,)?) => {
+        $(unsafe impl Sync for $ty {})*
+    };
+}
 
-// We need this as rustc complains that is is unsafe to share `*const u32`
-// pointers between threads. We only allow these to be created with static
-// data, so this is OK.
-unsafe impl Sync for MappingTableEntry {}
+impl_sync!(
+    Header,
+    MappingTableEntry,
+    entry::Addr,
+    entry::IdAndString,
+    entry::IdAndAddress,
+    entry::PinsWithName,
+    entry::Pins64WithName,
+    entry::NamedGroup,
+    entry::BlockDevice,
+    entry::PtrInt32WithName,
+    entry::PtrStringWithName,
+    entry::PtrInt32Limits,
+    config::ConfigInt,
+);
 
-// We need this as rustc complains that is is unsafe to share `*const u32`
-// pointers between threads. We only allow these to be created with static
-// data, so this is OK.
-unsafe impl Sync for entry::Addr {}
+unsafe impl<const N: usize> Sync for config::ConfigString<N> {}
 
 // End of file
@@ -0,0 +1,147 @@
+//! Lists
+//!
+//! Functions for turning a comma-separated list of strings (e.g. from an
+//! environment variable) into one [`IdAndString`] entry per item, at compile
+//! time.
+//!
+//! We cannot point an Entry into the middle of a `&str` as the items are not
+//! null-terminated, so [`split`] first makes a copy of the list with every
+//! comma replaced by a null. The [`build_info!`](crate::build_info!) macro
+//! shows how these fit together.
+
+use crate::entry::{Addr, Common, IdAndString};
+
+/// Count the items in a comma-separated list. An empty list has no items.
+pub const fn count(list: &str) -> usize {
+    let bytes = list.as_bytes();
+    if bytes.is_empty() {
+        return 0;
+    }
+    let mut count = 1;
+    let mut idx = 0;
+    while idx < bytes.len() {
+        if bytes[idx] == b',' {
+            count += 1;
+        }
+        idx += 1;
+    }
+    count
+}
+
+/// Copy a comma-separated list, replacing each comma with a null and adding
+/// a null on the end.
+///
+/// `N` must be one more than the length of `list`.
+pub const fn split<const N: usize>(list: &str) -> [u8; N] {
+    let bytes = list.as_bytes();
+    if N != bytes.len() + 1 {
+        panic!("the output must be one byte longer than the list");
+    }
+    let mut output = [0u8; N];
+    let mut idx = 0;
+    while idx < bytes.len() {
+        output[idx] = match bytes[idx] {
+            b',' => 0,
+            0 => panic!("the list must not contain a null"),
+            b => b,
+        };
+        idx += 1;
+    }
+    output
+}
+
+/// Create an [`IdAndString`] entry for each null-terminated item in `names`
+/// (as produced by [`split`]), all with the same tag and ID.
+///
+/// `N` must be the number of items, as given by [`count`].
+pub const fn entries<const N: usize, const L: usize>(
+    tag: u16,
+    id: u32,
+    names: &'static [u8; L],
+) -> [IdAndString; N] {
+    let mut output = [IdAndString::EMPTY; N];
+    let mut start = 0;
+    let mut item = 0;
+    while item < N {
+        if start >= L {
+            panic!("there are fewer items than expected");
+        }
+        // Safety: `start` is within `names`, as we just checked
+        let value = unsafe { names.as_ptr().add(start) };
+        output[item] = IdAndString {
+            header: Common::of::<IdAndString>(tag),
+            id,
+            value,
+        };
+        while names[start] != 0 {
+            start += 1;
+        }
+        start += 1;
+        item += 1;
+    }
+    output
+}
+
+/// Get the address of each entry in an array, for putting in the Entry
+/// Table.
+pub const fn addrs<const N: usize>(entries: &'static [IdAndString; N]) -> [Addr; N] {
+    let mut output = [Addr::NULL; N];
+    let mut idx = 0;
+    while idx < N {
+        output[idx] = entries[idx].addr();
+        idx += 1;
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ID_RP_PROGRAM_FEATURE, TAG_RASPBERRY_PI};
+
+    #[test]
+    fn count_items() {
+        assert_eq!(count(""), 0);
+        assert_eq!(count("defmt"), 1);
+        assert_eq!(count("defmt,usb-logging"), 2);
+        assert_eq!(count("a,,b"), 3);
+    }
+
+    #[test]
+    fn split_items() {
+        assert_eq!(split::<1>(""), *b"\0");
+        assert_eq!(split::<6>("defmt"), *b"defmt\0");
+        assert_eq!(split::<18>("defmt,usb-logging"), *b"defmt\0usb-logging\0");
+    }
+
+    #[test]
+    fn no_entries() {
+        static NAMES: [u8; 1] = split("");
+        static ENTRIES: [IdAndString; 0] = entries(TAG_RASPBERRY_PI, ID_RP_PROGRAM_FEATURE, &NAMES);
+        assert!(ENTRIES.is_empty());
+        assert!(addrs(&ENTRIES).is_empty());
+    }
+
+    #[test]
+    fn one_entry() {
+        static NAMES: [u8; 6] = split("defmt");
+        static ENTRIES: [IdAndString; 1] = entries(TAG_RASPBERRY_PI, ID_RP_PROGRAM_FEATURE, &NAMES);
+        assert_eq!(ENTRIES[0].header.data_type(), crate::DataType::IdAndString);
+        assert_eq!(ENTRIES[0].header.tag(), TAG_RASPBERRY_PI);
+        assert_eq!(ENTRIES[0].id, ID_RP_PROGRAM_FEATURE);
+        assert_eq!(ENTRIES[0].value, NAMES.as_ptr());
+    }
+
+    #[test]
+    fn entries_point_at_each_item() {
+        static NAMES: [u8; 18] = split("defmt,usb-logging");
+        static ENTRIES: [IdAndString; 2] = entries(TAG_RASPBERRY_PI, ID_RP_PROGRAM_FEATURE, &NAMES);
+        assert_eq!(ENTRIES[0].value, NAMES.as_ptr());
+        assert_eq!(ENTRIES[1].value, NAMES[6..].as_ptr());
+        let addrs = addrs(&ENTRIES);
+        assert_eq!(addrs[0].as_ptr(), ENTRIES[0].addr().as_ptr());
+        assert_eq!(addrs[1].as_ptr(), ENTRIES[1].addr().as_ptr());
+    }
+}
+
+// End of file
@@ -0,0 +1,366 @@
+//! Macros
+//!
+//! Macros for declaring Entries and placing them in the Entry Table.
+
+/// Declare one or more 'Binary Info' entries and add them to the Entry Table.
+///
+/// This is the equivalent of `bi_decl()` in the [pico-sdk]. Each `static` is
+/// declared as written, and alongside it we declare a hidden
+/// [`entry::Addr`](crate::entry::Addr) which points at it. That hidden value
+/// is placed in the `.bi_entries` linker section, so you no longer need to
+/// maintain the Entry Table by hand.
+///
+/// ```
+/// rp_binary_info::bi_decl! {
+///     static PROGRAM_NAME: rp_binary_info::entry::IdAndString =
+///         rp_binary_info::program_name("my tool\0");
+///     static NUMBER_OF_KITTENS: rp_binary_info::entry::IdAndInt =
+///         rp_binary_info::custom_integer(rp_binary_info::make_tag(b'J', b'P'), 1, 0x12345678);
+/// }
+/// ```
+///
+/// [pico-sdk]: https://github.com/raspberrypi/pico-sdk
+#[macro_export]
+macro_rules! bi_decl {
+    ($($(#[$attr:meta])* $vis:vis static $name:ident : $ty:ty = $init:expr;)*) => {
+        $(
+            $(#[$attr])*
+            $vis static $name: $ty = $init;
+
+            const _: () = {
+                #[link_section = ".bi_entries"]
+                #[used]
+                static ENTRY_ADDR: $crate::entry::Addr = $crate::entry::Addr::of(&$name);
+            };
+        )*
+    };
+}
+
+/// Declare the 'Binary Info' [`Header`](crate::Header), and the mapping table
+/// it points to.
+///
+/// The header goes in the `.bi_header` linker section, and points at the
+/// Entry Table using the `__bi_entries_start` and `__bi_entries_end` symbols
+/// from your linker script. The mapping table has one entry, for the `.data`
+/// section, using the `__sidata`, `__sdata` and `__edata` symbols that
+/// [cortex-m-rt](https://github.com/rust-embedded/cortex-m-rt) provides.
+///
+/// You need exactly one of these in your application:
+///
+/// ```no_run
+/// # #[no_mangle]
+/// # static __bi_entries_start: u32 = 0;
+/// # #[no_mangle]
+/// # static __bi_entries_end: u32 = 0;
+/// # #[no_mangle]
+/// # static __sdata: u32 = 0;
+/// # #[no_mangle]
+/// # static __edata: u32 = 0;
+/// # #[no_mangle]
+/// # static __sidata: u32 = 0;
+/// rp_binary_info::binary_info_header!();
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! binary_info_header {
+    () => {
+        const _: () = {
+            extern "C" {
+                static __bi_entries_start: $crate::entry::Addr;
+                static __bi_entries_end: $crate::entry::Addr;
+                static __sdata: u32;
+                static __edata: u32;
+                static __sidata: u32;
+            }
+
+            /// Picotool can find this block in our ELF file and report
+            /// interesting metadata.
+            #[link_section = ".bi_header"]
+            #[used]
+            static PICOTOOL_META: $crate::Header = unsafe {
+                $crate::Header::new(
+                    &__bi_entries_start,
+                    &__bi_entries_end,
+                    MAPPING_TABLE.as_slice(),
+                )
+            };
+
+            /// This tells picotool how to convert RAM addresses back into
+            /// Flash addresses
+            static MAPPING_TABLE: $crate::MappingTable<1> = $crate::MappingTable::new([
+                // This is the entry for .data
+                $crate::MappingTableEntry::new(
+                    unsafe { &__sidata },
+                    unsafe { &__sdata },
+                    unsafe { &__edata },
+                ),
+            ]);
+        };
+    };
+}
+
+/// Declare an `ID_RP_BINARY_END` entry, set to the address of a linker
+/// symbol which marks the end of your program in Flash.
+///
+/// `picotool` uses this entry to work out how much Flash to read when you
+/// run `picotool save`. By default we use the symbol `__flash_binary_end`,
+/// which our `binary_info.x` linker script fragment defines. If you don't use
+/// that, you can define it by adding this to your `memory.x`:
+///
+/// ```ld
+/// /* The end of everything that cortex-m-rt puts in Flash. Coming after .bss,
+///  * this is past .data's initialisers and anything after them (like
+///  * .gnu.sgstubs). */
+/// SECTIONS {
+///     .bi_flash_end (NOLOAD) :
+///     {
+///         PROVIDE(__flash_binary_end = .);
+///     } > FLASH
+/// } INSERT AFTER .bss;
+/// ```
+///
+/// Then, in your application:
+///
+/// ```no_run
+/// # #[no_mangle]
+/// # static __flash_binary_end: u8 = 0;
+/// rp_binary_info::binary_end!();
+/// # fn main() {}
+/// ```
+///
+/// If your linker script already has a suitable symbol, you can pass its name
+/// instead, like `rp_binary_info::binary_end!(__my_end_symbol);`.
+#[macro_export]
+macro_rules! binary_end {
+    () => {
+        $crate::binary_end!(__flash_binary_end);
+    };
+    ($symbol:ident) => {
+        const _: () = {
+            extern "C" {
+                static $symbol: u8;
+            }
+
+            $crate::bi_decl! {
+                static BINARY_END: $crate::entry::IdAndAddress =
+                    $crate::binary_end_address(unsafe { &$symbol });
+            }
+        };
+    };
+}
+
+/// Declare a [`ConfigInt`](crate::config::ConfigInt) variable, and a
+/// [`PtrInt32WithName`](crate::entry::PtrInt32WithName) entry which points at
+/// it, so you can change its default value with `picotool config`.
+///
+/// This is the equivalent of `bi_ptr_int32()` in the [pico-sdk]. The tag and
+/// ID identify the group the variable belongs to, and the entry is labelled
+/// with the variable's name. The variable is placed in `.data`, so its
+/// default value is in Flash where `picotool` can find it.
+///
+/// You can also give a minimum and/or maximum value, and the number of bits
+/// the value must fit in (as either a signed or an unsigned integer). These
+/// are checked against the default value at compile-time. If you give any of
+/// them, they are also recorded in a
+/// [`PtrInt32Limits`](crate::entry::PtrInt32Limits) entry so that
+/// [`patch`](crate::patch) can check any new value. `picotool` itself
+/// ignores them.
+///
+/// ```
+/// rp_binary_info::bi_ptr_int32!(
+///     rp_binary_info::make_tag(b'J', b'P'),
+///     0x1234_5678,
+///     /// The baud rate for the debug UART
+///     pub static UART_BAUD = 115_200,
+///     min = 9_600,
+///     max = 921_600,
+///     bits = 20
+/// );
+///
+/// let baud = UART_BAUD.get();
+/// # assert_eq!(baud, 115_200);
+/// ```
+///
+/// [pico-sdk]: https://github.com/raspberrypi/pico-sdk
+#[macro_export]
+macro_rules! bi_ptr_int32 {
+    (
+        @variable $tag:expr,
+        $id:expr,
+        $(#[$attr:meta])* $vis:vis static $name:ident = $default:expr
+    ) => {
+        $(#[$attr])*
+        #[link_section = ".data"]
+        $vis static $name: $crate::config::ConfigInt = $crate::config::ConfigInt::new($default);
+
+        const _: () = {
+            $crate::bi_decl! {
+                static ENTRY: $crate::entry::PtrInt32WithName = $crate::ptr_int32_with_name(
+                    $tag,
+                    $id,
+                    &$name,
+                    concat!(stringify!($name), "\0"),
+                );
+            }
+        };
+    };
+    (
+        $tag:expr,
+        $id:expr,
+        $(#[$attr:meta])* $vis:vis static $name:ident = $default:expr
+        $(,)?
+    ) => {
+        $crate::bi_ptr_int32!(@variable $tag, $id, $(#[$attr])* $vis static $name = $default);
+    };
+    (
+        $tag:expr,
+        $id:expr,
+        $(#[$attr:meta])* $vis:vis static $name:ident = $default:expr
+        $(, min = $min:expr)?
+        $(, max = $max:expr)?
+        $(, bits = $bits:expr)?
+        $(,)?
+    ) => {
+        $crate::bi_ptr_int32!(@variable $tag, $id, $(#[$attr])* $vis static $name = $default);
+
+        $(
+            const _: () = if ($default) < ($min) {
+                panic!("the default value is less than the minimum");
+            };
+        )?
+        $(
+            const _: () = if ($default) > ($max) {
+                panic!("the default value is more than the maximum");
+            };
+        )?
+        $(
+            const _: () = if !$crate::config::int_allowed(($default) as i64, i32::MIN, i32::MAX, $bits) {
+                panic!("the default value does not fit in the number of bits");
+            };
+        )?
+
+        const _: () = {
+            // Each of these is the last value in its list, so the default
+            // unless one was given
+            const MIN: i32 = {
+                let min: &[i32] = &[i32::MIN $(, $min)?];
+                min[min.len() - 1]
+            };
+            const MAX: i32 = {
+                let max: &[i32] = &[i32::MAX $(, $max)?];
+                max[max.len() - 1]
+            };
+            const BITS: u32 = {
+                let bits: &[u32] = &[32 $(, $bits)?];
+                bits[bits.len() - 1]
+            };
+
+            $crate::bi_decl! {
+                static LIMITS: $crate::entry::PtrInt32Limits =
+                    $crate::ptr_int32_limits($tag, $id, &$name, MIN, MAX, BITS);
+            }
+        };
+    };
+}
+
+/// Declare a [`ConfigString`](crate::config::ConfigString) variable, and a
+/// [`PtrStringWithName`](crate::entry::PtrStringWithName) entry which points
+/// at it, so you can change its default value with `picotool config`.
+///
+/// This is the equivalent of `bi_ptr_string()` in the [pico-sdk]. The tag and
+/// ID identify the group the variable belongs to, and the entry is labelled
+/// with the variable's name. The variable is placed in `.data`, so its
+/// default value is in Flash where `picotool` can find it.
+///
+/// `max_len` is the size of the buffer, including the null terminator, so
+/// the string can be at most `max_len - 1` bytes long.
+///
+/// ```
+/// rp_binary_info::bi_ptr_string!(
+///     rp_binary_info::make_tag(b'J', b'P'),
+///     0x1234_5678,
+///     /// The name we give ourselves on the network
+///     pub static DEVICE_NAME = "widget",
+///     max_len = 32
+/// );
+///
+/// let name: &core::ffi::CStr = DEVICE_NAME.get();
+/// # assert_eq!(name.to_bytes(), b"widget");
+/// ```
+///
+/// [pico-sdk]: https://github.com/raspberrypi/pico-sdk
+#[macro_export]
+macro_rules! bi_ptr_string {
+    (
+        $tag:expr,
+        $id:expr,
+        $(#[$attr:meta])* $vis:vis static $name:ident = $default:expr,
+        max_len = $max_len:expr
+        $(,)?
+    ) => {
+        $(#[$attr])*
+        #[link_section = ".data"]
+        $vis static $name: $crate::config::ConfigString<{ $max_len }> =
+            $crate::config::ConfigString::new($default);
+
+        const _: () = {
+            $crate::bi_decl! {
+                static ENTRY: $crate::entry::PtrStringWithName = $crate::ptr_string_with_name(
+                    $tag,
+                    $id,
+                    &$name,
+                    concat!(stringify!($name), "\0"),
+                );
+            }
+        };
+    };
+}
+
+/// Declare entries for the build metadata passed in by
+/// [`build::emit`](crate::build::emit) in your `build.rs`.
+///
+/// This gives you:
+///
+/// * a program version, from `git describe`
+/// * a build date
+/// * two build attributes - `Debug` or `Release`, and the target triple
+/// * a program feature for each Cargo feature enabled in your application
+///
+/// ```no_run
+/// rp_binary_info::build_info!();
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! build_info {
+    () => {
+        const _: () = {
+            $crate::bi_decl! {
+                static VERSION: $crate::entry::IdAndString =
+                    $crate::version(concat!(env!("RP_BINARY_INFO_GIT_VERSION"), "\0"));
+                static BUILD_DATE: $crate::entry::IdAndString =
+                    $crate::build_date(concat!(env!("RP_BINARY_INFO_BUILD_DATE"), "\0"));
+                static PROFILE: $crate::entry::IdAndString =
+                    $crate::build_attribute(concat!(env!("RP_BINARY_INFO_PROFILE"), "\0"));
+                static TARGET: $crate::entry::IdAndString =
+                    $crate::build_attribute(concat!(env!("RP_BINARY_INFO_TARGET"), "\0"));
+            }
+
+            const FEATURES: &str = env!("RP_BINARY_INFO_FEATURES");
+            const NUM_FEATURES: usize = $crate::list::count(FEATURES);
+
+            static FEATURE_NAMES: [u8; FEATURES.len() + 1] = $crate::list::split(FEATURES);
+
+            static FEATURE_ENTRIES: [$crate::entry::IdAndString; NUM_FEATURES] =
+                $crate::list::entries(
+                    $crate::TAG_RASPBERRY_PI,
+                    $crate::ID_RP_PROGRAM_FEATURE,
+                    &FEATURE_NAMES,
+                );
+
+            #[link_section = ".bi_entries"]
+            #[used]
+            static FEATURE_ADDRS: [$crate::entry::Addr; NUM_FEATURES] =
+                $crate::list::addrs(&FEATURE_ENTRIES);
+        };
+    };
+}
@@ -0,0 +1,1575 @@
+//! Parsing
+//!
+//! Types and Functions for reading 'Binary Info' back out of a compiled
+//! program, on the host. This does the same job as `picotool info`.
+//!
+//! The Entries we embed are full of 32-bit pointers, so we cannot just cast
+//! the bytes back into the types in [`entry`](crate::entry) - we have to
+//! decode them one field at a time.
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::entry::PtrInt32Limits;
+use crate::{DataType, Header};
+
+/// How far from the start of the image we look for the [`Header`].
+///
+/// This is as far as `picotool` looks on any chip. See [`Chip`] for the
+/// stricter rules for each chip.
+pub const HEADER_SEARCH_LEN: u32 = 0x1000;
+
+/// The size of a [`Header`], in bytes
+const HEADER_LEN: u32 = 20;
+
+/// Where Flash starts, on both the RP2040 and the RP2350
+const FLASH_BASE: u32 = 0x1000_0000;
+
+/// The longest string we will read before giving up on finding the null
+/// terminator.
+pub const MAX_STRING_LEN: u32 = 1024;
+
+/// The most entries we will read from a Mapping Table before deciding the
+/// terminator is missing.
+const MAX_MAPPINGS: u32 = 64;
+
+/// Something we can read a program's memory from.
+///
+/// Addresses are the addresses the program was linked at, so Flash starts at
+/// `0x1000_0000`.
+pub trait Memory {
+    /// Get `len` bytes starting at `address`, or `None` if they are not all
+    /// present in the image.
+    fn read(&self, address: u32, len: u32) -> Option<&[u8]>;
+}
+
+/// A contiguous image of memory, such as a `.bin` file.
+pub struct FlatImage<'a> {
+    data: &'a [u8],
+    base_address: u32,
+}
+
+/// A sparse image of memory, made up of contiguous runs of bytes, such as
+/// we get from a UF2 file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SparseImage {
+    /// Runs of bytes, keyed by start address. Runs never overlap or touch.
+    runs: BTreeMap<u32, Vec<u8>>,
+}
+
+/// The chips that `picotool` can read 'Binary Info' from.
+///
+/// Each chip has different rules about where the [`Header`] must be:
+///
+/// * On the RP2040, `picotool` looks in the 256 bytes after the 256 byte
+///   `boot2` block at the start of Flash. A program which runs from RAM has
+///   no `boot2`, so it looks in the first 256 bytes of the program instead.
+/// * On the RP2350, `picotool` looks in the first 4 KiB of the program,
+///   alongside the image definition block.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Chip {
+    Rp2040,
+    Rp2350,
+}
+
+/// An entry from the Mapping Table in the [`Header`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Mapping {
+    /// The start address in Flash
+    pub source_addr_start: u32,
+    /// The start address in RAM
+    pub dest_addr_start: u32,
+    /// The end address in RAM
+    pub dest_addr_end: u32,
+}
+
+/// All the 'Binary Info' we found in an image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct BinaryInfo {
+    /// Where we found the [`Header`]
+    pub header_address: u32,
+    /// The decoded Entries, in the order they appear in the Entry Table
+    pub entries: Vec<Entry>,
+    /// The RAM/Flash address mapping table, without the terminator
+    pub mapping_table: Vec<Mapping>,
+}
+
+/// A decoded 'Binary Info' entry.
+///
+/// Any pointers have been followed (via the Mapping Table, where required)
+/// and the values they point at have been copied out.
+///
+/// Every `address` is a run-time address - the address the program uses,
+/// which is in RAM for anything copied there at start-up. Use
+/// [`Mapping::resolve`] to find where it is in the image. The serialized
+/// form gives the same addresses.
+///
+/// With the `serde` feature enabled, every entry serializes to a structure
+/// with the same five fields:
+///
+/// * `data_type` - the kind of entry, in `snake_case` (e.g. `id_and_string`)
+/// * `tag` - the tag from the entry's header
+/// * `id` - the entry's ID, or `null` if it doesn't have one. A `NamedGroup`
+///   has `null` here, and gives its parent's tag and ID in its value as
+///   `parent_tag` and `parent_id`
+/// * `name` - the [`well_known_name`] for the tag and ID, or `null`
+/// * `value` - the entry's payload; a string or an integer for
+///   `IdAndString` and `IdAndInt`, an array of bytes for `SizedData`,
+///   otherwise a structure
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Entry {
+    /// See [`entry::IdAndString`](crate::entry::IdAndString)
+    IdAndString { tag: u16, id: u32, value: String },
+    /// See [`entry::IdAndInt`](crate::entry::IdAndInt)
+    IdAndInt { tag: u16, id: u32, value: u32 },
+    /// See [`entry::PinsWithFunction`](crate::entry::PinsWithFunction)
+    PinsWithFunction {
+        tag: u16,
+        function: u8,
+        pins: Vec<u8>,
+    },
+    /// See [`entry::PinsWithName`](crate::entry::PinsWithName)
+    PinsWithName {
+        tag: u16,
+        pin_mask: u32,
+        label: String,
+    },
+    /// See [`entry::Pins64WithFunction`](crate::entry::Pins64WithFunction)
+    Pins64WithFunction {
+        tag: u16,
+        function: u8,
+        pins: Vec<u8>,
+    },
+    /// See [`entry::Pins64WithName`](crate::entry::Pins64WithName)
+    Pins64WithName {
+        tag: u16,
+        pin_mask: u64,
+        label: String,
+    },
+    /// See [`entry::NamedGroup`](crate::entry::NamedGroup)
+    NamedGroup {
+        parent_tag: u16,
+        parent_id: u32,
+        flags: u16,
+        group_tag: u16,
+        group_id: u32,
+        label: String,
+    },
+    /// See [`entry::BlockDevice`](crate::entry::BlockDevice)
+    BlockDevice {
+        tag: u16,
+        name: String,
+        address: u32,
+        size: u32,
+        flags: u16,
+    },
+    /// See [`entry::PtrInt32WithName`](crate::entry::PtrInt32WithName)
+    ///
+    /// `address` is the run-time address of the variable, and `value` is its
+    /// default value from the image.
+    PtrInt32WithName {
+        tag: u16,
+        id: u32,
+        label: String,
+        value: i32,
+        address: u32,
+    },
+    /// See [`entry::PtrStringWithName`](crate::entry::PtrStringWithName)
+    ///
+    /// `address` is the run-time address of the variable, `value` is its
+    /// default value from the image, and `max_len` is the size of its buffer
+    /// (including the null terminator).
+    PtrStringWithName {
+        tag: u16,
+        id: u32,
+        label: String,
+        value: String,
+        max_len: u32,
+        address: u32,
+    },
+    /// See [`entry::PtrInt32Limits`](crate::entry::PtrInt32Limits)
+    ///
+    /// `address` is the run-time address of the variable, as in its
+    /// `PtrInt32WithName` entry.
+    PtrInt32Limits {
+        tag: u16,
+        id: u32,
+        address: u32,
+        min: i32,
+        max: i32,
+        bits: u32,
+    },
+    /// See [`entry::Raw`](crate::entry::Raw)
+    ///
+    /// The entry does not record how long its data is, so `address` is where
+    /// the data starts, for you to read as much as you expect to be there.
+    Raw { tag: u16, address: u32 },
+    /// See [`entry::SizedData`](crate::entry::SizedData)
+    SizedData { tag: u16, data: Vec<u8> },
+    /// An entry with a data type we do not know how to decode
+    Unknown {
+        data_type: u16,
+        tag: u16,
+        address: u32,
+    },
+}
+
+/// The ways in which parsing can fail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// We could not find a [`Header`] in the image
+    HeaderNotFound,
+    /// The Entry Table in the [`Header`] ends before it starts, or is not a
+    /// whole number of entries long
+    BadEntryTable { start: u32, end: u32 },
+    /// The Mapping Table has no null terminator, or has an entry which maps
+    /// to addresses that do not fit in 32 bits
+    BadMappingTable { address: u32 },
+    /// Something we needed to read was not in the image
+    OutOfBounds { address: u32 },
+    /// A string had no null terminator
+    UnterminatedString { address: u32 },
+    /// The [`Header`] is somewhere `picotool` will not look for it
+    HeaderMisplaced { address: u32, chip: Chip },
+}
+
+impl<'a> FlatImage<'a> {
+    /// Create a new image from some bytes, where the first byte is at
+    /// `base_address` (usually `0x1000_0000`).
+    pub const fn new(data: &'a [u8], base_address: u32) -> FlatImage<'a> {
+        FlatImage { data, base_address }
+    }
+}
+
+impl<'a> Memory for FlatImage<'a> {
+    fn read(&self, address: u32, len: u32) -> Option<&[u8]> {
+        let offset = address.checked_sub(self.base_address)? as usize;
+        let end = offset.checked_add(len as usize)?;
+        self.data.get(offset..end)
+    }
+}
+
+impl SparseImage {
+    /// Create a new, empty, image.
+    pub fn new() -> SparseImage {
+        SparseImage::default()
+    }
+
+    /// Copy some bytes into the image at the given address, replacing
+    /// anything already there.
+    pub fn insert(&mut self, address: u32, data: &[u8]) {
+        let mut start = address;
+        let mut bytes = Vec::new();
+
+        // Join on to the end of any run which reaches us
+        if let Some((&prev_start, prev)) = self.runs.range(..=address).next_back() {
+            if u64::from(prev_start) + prev.len() as u64 >= u64::from(address) {
+                start = prev_start;
+                bytes = self.runs.remove(&prev_start).unwrap_or_default();
+            }
+        }
+
+        let offset = (address - start) as usize;
+        if bytes.len() < offset + data.len() {
+            bytes.resize(offset + data.len(), 0);
+        }
+        bytes[offset..offset + data.len()].copy_from_slice(data);
+
+        // Swallow any runs which we now reach, keeping our new data where
+        // they overlap
+        let end = u64::from(start) + bytes.len() as u64;
+        let following: Vec<u32> = self
+            .runs
+            .range(address..)
+            .take_while(|(&next_start, _)| u64::from(next_start) <= end)
+            .map(|(&next_start, _)| next_start)
+            .collect();
+        for next_start in following {
+            let next = self.runs.remove(&next_start).unwrap_or_default();
+            let next_offset = (next_start - start) as usize;
+            if next_offset + next.len() > bytes.len() {
+                bytes.extend_from_slice(&next[bytes.len() - next_offset..]);
+            }
+        }
+
+        self.runs.insert(start, bytes);
+    }
+
+    /// The lowest address in the image, if it is not empty.
+    pub fn base_address(&self) -> Option<u32> {
+        self.runs.keys().next().copied()
+    }
+
+    /// Iterate through the contiguous runs of bytes in the image, in address
+    /// order.
+    pub fn runs(&self) -> impl Iterator<Item = (u32, &[u8])> {
+        self.runs
+            .iter()
+            .map(|(&address, bytes)| (address, bytes.as_slice()))
+    }
+}
+
+impl Memory for SparseImage {
+    fn read(&self, address: u32, len: u32) -> Option<&[u8]> {
+        let (&start, bytes) = self.runs.range(..=address).next_back()?;
+        let offset = (address - start) as usize;
+        let end = offset.checked_add(len as usize)?;
+        bytes.get(offset..end)
+    }
+}
+
+impl Chip {
+    /// The range of addresses where the whole [`Header`] must be, for a
+    /// program which starts at `base_address`.
+    pub fn header_window(&self, base_address: u32) -> Result<core::ops::Range<u32>, Error> {
+        let start = match self {
+            Chip::Rp2040 if base_address == FLASH_BASE => add_offset(base_address, 0x100)?,
+            _ => base_address,
+        };
+        let len = match self {
+            Chip::Rp2040 => 0x100,
+            Chip::Rp2350 => 0x1000,
+        };
+        Ok(start..add_offset(start, len)?)
+    }
+}
+
+impl Mapping {
+    /// Convert a run-time address into the address where the data lives in
+    /// the image, if this mapping covers it.
+    ///
+    /// Gives `None` if the address in the image would not fit in 32 bits,
+    /// but [`parse`] rejects any Mapping Table with such an entry.
+    pub fn resolve(&self, address: u32) -> Option<u32> {
+        if address >= self.dest_addr_start && address < self.dest_addr_end {
+            self.source_addr_start
+                .checked_add(address - self.dest_addr_start)
+        } else {
+            None
+        }
+    }
+
+    /// Check every address this mapping covers can be converted.
+    fn is_valid(&self) -> bool {
+        self.dest_addr_start <= self.dest_addr_end
+            && self
+                .source_addr_start
+                .checked_add(self.dest_addr_end - self.dest_addr_start)
+                .is_some()
+    }
+}
+
+impl Entry {
+    /// Get the data type of this entry, in `snake_case`.
+    pub fn data_type_name(&self) -> &'static str {
+        match self {
+            Entry::IdAndString { .. } => "id_and_string",
+            Entry::IdAndInt { .. } => "id_and_int",
+            Entry::PinsWithFunction { .. } => "pins_with_function",
+            Entry::PinsWithName { .. } => "pins_with_name",
+            Entry::Pins64WithFunction { .. } => "pins64_with_function",
+            Entry::Pins64WithName { .. } => "pins64_with_name",
+            Entry::NamedGroup { .. } => "named_group",
+            Entry::BlockDevice { .. } => "block_device",
+            Entry::PtrInt32WithName { .. } => "ptr_int32_with_name",
+            Entry::PtrStringWithName { .. } => "ptr_string_with_name",
+            Entry::PtrInt32Limits { .. } => "ptr_int32_limits",
+            Entry::Raw { .. } => "raw",
+            Entry::SizedData { .. } => "sized_data",
+            Entry::Unknown { .. } => "unknown",
+        }
+    }
+
+    /// Get the tag from this entry's header.
+    ///
+    /// For a `NamedGroup`, this is the tag of the parent group.
+    pub fn tag(&self) -> u16 {
+        match self {
+            Entry::IdAndString { tag, .. }
+            | Entry::IdAndInt { tag, .. }
+            | Entry::PinsWithFunction { tag, .. }
+            | Entry::PinsWithName { tag, .. }
+            | Entry::Pins64WithFunction { tag, .. }
+            | Entry::Pins64WithName { tag, .. }
+            | Entry::BlockDevice { tag, .. }
+            | Entry::PtrInt32WithName { tag, .. }
+            | Entry::PtrStringWithName { tag, .. }
+            | Entry::PtrInt32Limits { tag, .. }
+            | Entry::Raw { tag, .. }
+            | Entry::SizedData { tag, .. }
+            | Entry::Unknown { tag, .. } => *tag,
+            Entry::NamedGroup { parent_tag, .. } => *parent_tag,
+        }
+    }
+
+    /// Get this entry's ID, if it has one.
+    ///
+    /// For a `NamedGroup`, this is the ID of the parent group.
+    pub fn id(&self) -> Option<u32> {
+        match self {
+            Entry::IdAndString { id, .. }
+            | Entry::IdAndInt { id, .. }
+            | Entry::PtrInt32WithName { id, .. }
+            | Entry::PtrStringWithName { id, .. }
+            | Entry::PtrInt32Limits { id, .. } => Some(*id),
+            Entry::NamedGroup { parent_id, .. } => Some(*parent_id),
+            _ => None,
+        }
+    }
+}
+
+impl BinaryInfo {
+    /// Check the [`Header`] is somewhere `picotool` will find it on the given
+    /// chip, for a program which starts at `base_address`.
+    pub fn check_placement(&self, chip: Chip, base_address: u32) -> Result<(), Error> {
+        let window = chip.header_window(base_address)?;
+        let header_end = add_offset(self.header_address, HEADER_LEN)?;
+        if self.header_address >= window.start && header_end <= window.end {
+            Ok(())
+        } else {
+            Err(Error::HeaderMisplaced {
+                address: self.header_address,
+                chip,
+            })
+        }
+    }
+
+    /// Find the limits recorded for the integer variable at the given
+    /// run-time address, as `(min, max, bits)`.
+    pub fn int_limits(&self, address: u32) -> Option<(i32, i32, u32)> {
+        self.entries.iter().find_map(|entry| match entry {
+            Entry::PtrInt32Limits {
+                address: a,
+                min,
+                max,
+                bits,
+                ..
+            } if *a == address => Some((*min, *max, *bits)),
+            _ => None,
+        })
+    }
+
+    /// Find the first `IdAndString` entry with the given tag and ID.
+    pub fn find_string(&self, tag: u16, id: u32) -> Option<&str> {
+        self.entries.iter().find_map(|entry| match entry {
+            Entry::IdAndString {
+                tag: t,
+                id: i,
+                value,
+            } if *t == tag && *i == id => Some(value.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Find the first `IdAndInt` entry with the given tag and ID.
+    pub fn find_int(&self, tag: u16, id: u32) -> Option<u32> {
+        self.entries.iter().find_map(|entry| match entry {
+            Entry::IdAndInt {
+                tag: t,
+                id: i,
+                value,
+            } if *t == tag && *i == id => Some(*value),
+            _ => None,
+        })
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::HeaderNotFound => write!(f, "no binary info header found"),
+            Error::BadEntryTable { start, end } => {
+                write!(f, "bad entry table from {:#010x} to {:#010x}", start, end)
+            }
+            Error::BadMappingTable { address } => {
+                write!(f, "bad mapping table at {:#010x}", address)
+            }
+            Error::OutOfBounds { address } => {
+                write!(f, "address {:#010x} is not in the image", address)
+            }
+            Error::UnterminatedString { address } => {
+                write!(f, "unterminated string at {:#010x}", address)
+            }
+            Error::HeaderMisplaced { address, chip } => write!(
+                f,
+                "binary info header at {:#010x} is too far into the image for the {}",
+                address, chip
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Chip {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Chip::Rp2040 => write!(f, "RP2040"),
+            Chip::Rp2350 => write!(f, "RP2350"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Entry {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        #[derive(serde::Serialize)]
+        struct Pins<'a> {
+            function: u8,
+            pins: &'a [u8],
+        }
+
+        #[derive(serde::Serialize)]
+        struct PinNames<'a> {
+            pin_mask: u32,
+            label: &'a str,
+        }
+
+        #[derive(serde::Serialize)]
+        struct Pin64Names<'a> {
+            pin_mask: u64,
+            label: &'a str,
+        }
+
+        #[derive(serde::Serialize)]
+        struct Group<'a> {
+            parent_tag: u16,
+            parent_id: u32,
+            group_tag: u16,
+            group_id: u32,
+            label: &'a str,
+            flags: u16,
+        }
+
+        #[derive(serde::Serialize)]
+        struct Device<'a> {
+            name: &'a str,
+            address: u32,
+            size: u32,
+            flags: u16,
+        }
+
+        #[derive(serde::Serialize)]
+        struct PtrInt32<'a> {
+            label: &'a str,
+            value: i32,
+            address: u32,
+        }
+
+        #[derive(serde::Serialize)]
+        struct PtrString<'a> {
+            label: &'a str,
+            value: &'a str,
+            max_len: u32,
+            address: u32,
+        }
+
+        #[derive(serde::Serialize)]
+        struct Limits {
+            address: u32,
+            min: i32,
+            max: i32,
+            bits: u32,
+        }
+
+        #[derive(serde::Serialize)]
+        struct RawData {
+            address: u32,
+        }
+
+        #[derive(serde::Serialize)]
+        struct Unknown {
+            data_type: u16,
+            address: u32,
+        }
+
+        let mut state = serializer.serialize_struct("Entry", 5)?;
+        state.serialize_field("data_type", self.data_type_name())?;
+        // A group's parent goes in its value, so it isn't mistaken for the
+        // group's own ID
+        let id = match self {
+            Entry::NamedGroup { .. } => None,
+            _ => self.id(),
+        };
+        state.serialize_field("tag", &self.tag())?;
+        state.serialize_field("id", &id)?;
+        state.serialize_field("name", &id.and_then(|id| well_known_name(self.tag(), id)))?;
+        match self {
+            Entry::IdAndString { value, .. } => state.serialize_field("value", value)?,
+            Entry::IdAndInt { value, .. } => state.serialize_field("value", value)?,
+            Entry::PinsWithFunction { function, pins, .. }
+            | Entry::Pins64WithFunction { function, pins, .. } => state.serialize_field(
+                "value",
+                &Pins {
+                    function: *function,
+                    pins,
+                },
+            )?,
+            Entry::PinsWithName {
+                pin_mask, label, ..
+            } => state.serialize_field(
+                "value",
+                &PinNames {
+                    pin_mask: *pin_mask,
+                    label,
+                },
+            )?,
+            Entry::Pins64WithName {
+                pin_mask, label, ..
+            } => state.serialize_field(
+                "value",
+                &Pin64Names {
+                    pin_mask: *pin_mask,
+                    label,
+                },
+            )?,
+            Entry::NamedGroup {
+                parent_tag,
+                parent_id,
+                flags,
+                group_tag,
+                group_id,
+                label,
+            } => state.serialize_field(
+                "value",
+                &Group {
+                    parent_tag: *parent_tag,
+                    parent_id: *parent_id,
+                    group_tag: *group_tag,
+                    group_id: *group_id,
+                    label,
+                    flags: *flags,
+                },
+            )?,
+            Entry::BlockDevice {
+                name,
+                address,
+                size,
+                flags,
+                ..
+            } => state.serialize_field(
+                "value",
+                &Device {
+                    name,
+                    address: *address,
+                    size: *size,
+                    flags: *flags,
+                },
+            )?,
+            Entry::PtrInt32WithName {
+                label,
+                value,
+                address,
+                ..
+            } => state.serialize_field(
+                "value",
+                &PtrInt32 {
+                    label,
+                    value: *value,
+                    address: *address,
+                },
+            )?,
+            Entry::PtrStringWithName {
+                label,
+                value,
+                max_len,
+                address,
+                ..
+            } => state.serialize_field(
+                "value",
+                &PtrString {
+                    label,
+                    value,
+                    max_len: *max_len,
+                    address: *address,
+                },
+            )?,
+            Entry::PtrInt32Limits {
+                address,
+                min,
+                max,
+                bits,
+                ..
+            } => state.serialize_field(
+                "value",
+                &Limits {
+                    address: *address,
+                    min: *min,
+                    max: *max,
+                    bits: *bits,
+                },
+            )?,
+            Entry::Raw { address, .. } => {
+                state.serialize_field("value", &RawData { address: *address })?
+            }
+            Entry::SizedData { data, .. } => state.serialize_field("value", data)?,
+            Entry::Unknown {
+                data_type, address, ..
+            } => state.serialize_field(
+                "value",
+                &Unknown {
+                    data_type: *data_type,
+                    address: *address,
+                },
+            )?,
+        }
+        state.end()
+    }
+}
+
+/// Get the well-known name for a tag and ID, if it is one of the `RP`
+/// tagged IDs (e.g. `ID_RP_PROGRAM_NAME` is `program_name`).
+pub fn well_known_name(tag: u16, id: u32) -> Option<&'static str> {
+    if tag != crate::TAG_RASPBERRY_PI {
+        return None;
+    }
+    let name = match id {
+        crate::ID_RP_PROGRAM_NAME => "program_name",
+        crate::ID_RP_PROGRAM_VERSION_STRING => "program_version_string",
+        crate::ID_RP_PROGRAM_BUILD_DATE_STRING => "program_build_date_string",
+        crate::ID_RP_BINARY_END => "binary_end",
+        crate::ID_RP_PROGRAM_URL => "program_url",
+        crate::ID_RP_PROGRAM_DESCRIPTION => "program_description",
+        crate::ID_RP_PROGRAM_FEATURE => "program_feature",
+        crate::ID_RP_PROGRAM_BUILD_ATTRIBUTE => "program_build_attribute",
+        crate::ID_RP_SDK_VERSION => "sdk_version",
+        crate::ID_RP_PICO_BOARD => "pico_board",
+        crate::ID_RP_BOOT2_NAME => "boot2_name",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Parse the 'Binary Info' in a Flash image, where the first byte of `image`
+/// is at `base_address` (usually `0x1000_0000`).
+pub fn parse(image: &[u8], base_address: u32) -> Result<BinaryInfo, Error> {
+    parse_memory(&FlatImage::new(image, base_address), base_address)
+}
+
+/// Parse the 'Binary Info' in some memory, looking for the [`Header`] in
+/// the [`HEADER_SEARCH_LEN`] bytes after `search_start`.
+pub fn parse_memory<M>(memory: &M, search_start: u32) -> Result<BinaryInfo, Error>
+where
+    M: Memory + ?Sized,
+{
+    let header_address = find_header(memory, search_start)?;
+    let entries_start = read_u32(memory, add_offset(header_address, 4)?)?;
+    let entries_end = read_u32(memory, add_offset(header_address, 8)?)?;
+    let mapping_table_address = read_u32(memory, add_offset(header_address, 12)?)?;
+
+    if entries_end < entries_start || (entries_end - entries_start) % 4 != 0 {
+        return Err(Error::BadEntryTable {
+            start: entries_start,
+            end: entries_end,
+        });
+    }
+
+    let mapping_table = read_mapping_table(memory, mapping_table_address)?;
+    let reader = Reader {
+        memory,
+        mapping_table: &mapping_table,
+    };
+
+    let mut entries = Vec::new();
+    let mut entry_ptr = entries_start;
+    while entry_ptr < entries_end {
+        // The entry is read through the Mapping Table, so keep its
+        // run-time address as it is
+        let entry_address = reader.read_u32(entry_ptr)?;
+        entries.push(reader.read_entry(entry_address)?);
+        entry_ptr += 4;
+    }
+
+    Ok(BinaryInfo {
+        header_address,
+        entries,
+        mapping_table,
+    })
+}
+
+/// Look for the start and end markers of a [`Header`].
+fn find_header<M>(memory: &M, search_start: u32) -> Result<u32, Error>
+where
+    M: Memory + ?Sized,
+{
+    for offset in (0..HEADER_SEARCH_LEN).step_by(4) {
+        let address = add_offset(search_start, offset)?;
+        let (start, end) = match (
+            read_u32(memory, address),
+            read_u32(memory, add_offset(address, 16)?),
+        ) {
+            (Ok(start), Ok(end)) => (start, end),
+            _ => continue,
+        };
+        if start == Header::MARKER_START && end == Header::MARKER_END {
+            return Ok(address);
+        }
+    }
+    Err(Error::HeaderNotFound)
+}
+
+/// Read the null-terminated Mapping Table.
+fn read_mapping_table<M>(memory: &M, address: u32) -> Result<Vec<Mapping>, Error>
+where
+    M: Memory + ?Sized,
+{
+    let mut mapping_table = Vec::new();
+    for idx in 0..MAX_MAPPINGS {
+        let mapping_address = add_offset(address, idx * 12)?;
+        let mapping = Mapping {
+            source_addr_start: read_u32(memory, mapping_address)?,
+            dest_addr_start: read_u32(memory, add_offset(mapping_address, 4)?)?,
+            dest_addr_end: read_u32(memory, add_offset(mapping_address, 8)?)?,
+        };
+        if mapping.source_addr_start == 0
+            && mapping.dest_addr_start == 0
+            && mapping.dest_addr_end == 0
+        {
+            return Ok(mapping_table);
+        }
+        if !mapping.is_valid() {
+            return Err(Error::BadMappingTable { address });
+        }
+        mapping_table.push(mapping);
+    }
+    Err(Error::BadMappingTable { address })
+}
+
+/// Add an offset to an address from the image, failing if the result does
+/// not fit in 32 bits.
+fn add_offset(address: u32, offset: u32) -> Result<u32, Error> {
+    address
+        .checked_add(offset)
+        .ok_or(Error::OutOfBounds { address })
+}
+
+fn read_u32<M>(memory: &M, address: u32) -> Result<u32, Error>
+where
+    M: Memory + ?Sized,
+{
+    let bytes = memory
+        .read(address, 4)
+        .ok_or(Error::OutOfBounds { address })?;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// Reads values, following run-time addresses through the Mapping Table.
+struct Reader<'a, M: ?Sized> {
+    memory: &'a M,
+    mapping_table: &'a [Mapping],
+}
+
+impl<'a, M> Reader<'a, M>
+where
+    M: Memory + ?Sized,
+{
+    /// Convert a run-time address into an address in the image.
+    fn resolve(&self, address: u32) -> u32 {
+        self.mapping_table
+            .iter()
+            .find_map(|mapping| mapping.resolve(address))
+            .unwrap_or(address)
+    }
+
+    fn read(&self, address: u32, len: u32) -> Result<&'a [u8], Error> {
+        let address = self.resolve(address);
+        self.memory
+            .read(address, len)
+            .ok_or(Error::OutOfBounds { address })
+    }
+
+    fn read_u16(&self, address: u32) -> Result<u16, Error> {
+        let bytes = self.read(address, 2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn read_u32(&self, address: u32) -> Result<u32, Error> {
+        let bytes = self.read(address, 4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn read_u64(&self, address: u32) -> Result<u64, Error> {
+        let low = self.read_u32(address)?;
+        let high = self.read_u32(add_offset(address, 4)?)?;
+        Ok(u64::from(low) | (u64::from(high) << 32))
+    }
+
+    /// Read a pointer to a null-terminated string, and then the string it
+    /// points at. A null pointer gives an empty string.
+    fn read_string_ptr(&self, address: u32) -> Result<String, Error> {
+        let ptr = self.read_u32(address)?;
+        if ptr == 0 {
+            return Ok(String::new());
+        }
+        self.read_string(ptr, MAX_STRING_LEN)
+    }
+
+    /// Read a null-terminated string of at most `max_len` bytes, including
+    /// the null terminator.
+    fn read_string(&self, address: u32, max_len: u32) -> Result<String, Error> {
+        let start = self.resolve(address);
+        let mut bytes = Vec::new();
+        for offset in 0..max_len {
+            match self.read(add_offset(address, offset)?, 1)?[0] {
+                0 => return Ok(String::from_utf8_lossy(&bytes).into_owned()),
+                b => bytes.push(b),
+            }
+        }
+        Err(Error::UnterminatedString { address: start })
+    }
+
+    /// Decode the entry at the given run-time address.
+    fn read_entry(&self, address: u32) -> Result<Entry, Error> {
+        const ID_AND_INT: u16 = DataType::IdAndInt as u16;
+        const ID_AND_STRING: u16 = DataType::IdAndString as u16;
+        const BLOCK_DEVICE: u16 = DataType::BlockDevice as u16;
+        const PINS_WITH_FUNCTION: u16 = DataType::PinsWithFunction as u16;
+        const PINS_WITH_NAME: u16 = DataType::PinsWithName as u16;
+        const NAMED_GROUP: u16 = DataType::NamedGroup as u16;
+        const PINS64_WITH_FUNCTION: u16 = DataType::Pins64WithFunction as u16;
+        const PINS64_WITH_NAME: u16 = DataType::Pins64WithName as u16;
+        const PTR_INT32_WITH_NAME: u16 = DataType::PtrInt32WithName as u16;
+        const PTR_STRING_WITH_NAME: u16 = DataType::PtrStringWithName as u16;
+        const RAW: u16 = DataType::Raw as u16;
+        const SIZED_DATA: u16 = DataType::SizedData as u16;
+
+        let data_type = self.read_u16(address)?;
+        let tag = self.read_u16(add_offset(address, 2)?)?;
+        let entry = match data_type {
+            ID_AND_INT => Entry::IdAndInt {
+                tag,
+                id: self.read_u32(add_offset(address, 4)?)?,
+                value: self.read_u32(add_offset(address, 8)?)?,
+            },
+            ID_AND_STRING => Entry::IdAndString {
+                tag,
+                id: self.read_u32(add_offset(address, 4)?)?,
+                value: self.read_string_ptr(add_offset(address, 8)?)?,
+            },
+            BLOCK_DEVICE => Entry::BlockDevice {
+                tag,
+                name: self.read_string_ptr(add_offset(address, 4)?)?,
+                address: self.read_u32(add_offset(address, 8)?)?,
+                size: self.read_u32(add_offset(address, 12)?)?,
+                flags: self.read_u16(add_offset(address, 20)?)?,
+            },
+            PINS_WITH_FUNCTION => {
+                let (function, pins) = decode_pins(self.read_u32(add_offset(address, 4)?)?);
+                Entry::PinsWithFunction {
+                    tag,
+                    function,
+                    pins,
+                }
+            }
+            PINS_WITH_NAME => Entry::PinsWithName {
+                tag,
+                pin_mask: self.read_u32(add_offset(address, 4)?)?,
+                label: self.read_string_ptr(add_offset(address, 8)?)?,
+            },
+            NAMED_GROUP => Entry::NamedGroup {
+                parent_tag: tag,
+                parent_id: self.read_u32(add_offset(address, 4)?)?,
+                flags: self.read_u16(add_offset(address, 8)?)?,
+                group_tag: self.read_u16(add_offset(address, 10)?)?,
+                group_id: self.read_u32(add_offset(address, 12)?)?,
+                label: self.read_string_ptr(add_offset(address, 16)?)?,
+            },
+            PINS64_WITH_FUNCTION => {
+                let (function, pins) = decode_pins64(self.read_u64(add_offset(address, 4)?)?);
+                Entry::Pins64WithFunction {
+                    tag,
+                    function,
+                    pins,
+                }
+            }
+            PINS64_WITH_NAME => Entry::Pins64WithName {
+                tag,
+                pin_mask: self.read_u64(add_offset(address, 4)?)?,
+                label: self.read_string_ptr(add_offset(address, 12)?)?,
+            },
+            PTR_INT32_WITH_NAME => {
+                let value_address = self.read_u32(add_offset(address, 8)?)?;
+                Entry::PtrInt32WithName {
+                    tag,
+                    id: self.read_u32(add_offset(address, 4)?)?,
+                    label: self.read_string_ptr(add_offset(address, 12)?)?,
+                    value: self.read_u32(value_address)? as i32,
+                    address: value_address,
+                }
+            }
+            PTR_STRING_WITH_NAME => {
+                let value_address = self.read_u32(add_offset(address, 8)?)?;
+                let max_len = self.read_u32(add_offset(address, 16)?)?;
+                // The whole buffer must be in the image, not just the string
+                // in it, so that it can be patched
+                self.read(value_address, max_len)?;
+                Entry::PtrStringWithName {
+                    tag,
+                    id: self.read_u32(add_offset(address, 4)?)?,
+                    label: self.read_string_ptr(add_offset(address, 12)?)?,
+                    value: self.read_string(value_address, max_len)?,
+                    max_len,
+                    address: value_address,
+                }
+            }
+            RAW => Entry::Raw {
+                tag,
+                address: add_offset(address, 4)?,
+            },
+            SIZED_DATA => {
+                let length = self.read_u32(add_offset(address, 4)?)?;
+                let data = self.read(add_offset(address, 8)?, length)?;
+                if length == PtrInt32Limits::LENGTH
+                    && data[..4] == PtrInt32Limits::MAGIC.to_le_bytes()
+                {
+                    Entry::PtrInt32Limits {
+                        tag,
+                        id: self.read_u32(add_offset(address, 12)?)?,
+                        address: self.read_u32(add_offset(address, 16)?)?,
+                        min: self.read_u32(add_offset(address, 20)?)? as i32,
+                        max: self.read_u32(add_offset(address, 24)?)? as i32,
+                        bits: self.read_u32(add_offset(address, 28)?)?,
+                    }
+                } else {
+                    Entry::SizedData {
+                        tag,
+                        data: data.to_vec(),
+                    }
+                }
+            }
+            _ => Entry::Unknown {
+                data_type,
+                tag,
+                address,
+            },
+        };
+        Ok(entry)
+    }
+}
+
+/// Unpack the function and pin list from a `PinsWithFunction` entry.
+fn decode_pins(pin_encoding: u32) -> (u8, Vec<u8>) {
+    use crate::entry::PinsWithFunction;
+
+    let function = ((pin_encoding >> 3) & 0xF) as u8;
+    let pin = |idx: usize| ((pin_encoding >> (7 + (idx * 5))) & 0x1F) as u8;
+    let mut pins = Vec::new();
+    match pin_encoding & 0x7 {
+        PinsWithFunction::ENCODING_RANGE => pins.extend(pin(0)..=pin(1)),
+        PinsWithFunction::ENCODING_MULTI => {
+            for idx in 0..PinsWithFunction::MAX_PINS {
+                let next = pin(idx);
+                if pins.last() == Some(&next) {
+                    break;
+                }
+                pins.push(next);
+            }
+        }
+        _ => {}
+    }
+    (function, pins)
+}
+
+/// Unpack the function and pin list from a `Pins64WithFunction` entry.
+fn decode_pins64(pin_encoding: u64) -> (u8, Vec<u8>) {
+    use crate::entry::Pins64WithFunction;
+
+    let function = ((pin_encoding >> 3) & 0x1F) as u8;
+    let pin = |idx: usize| ((pin_encoding >> (8 + (idx * 8))) & 0xFF) as u8;
+    let mut pins = Vec::new();
+    match pin_encoding & 0x7 {
+        Pins64WithFunction::ENCODING_RANGE => pins.extend(pin(0)..=pin(1)),
+        Pins64WithFunction::ENCODING_MULTI => {
+            for idx in 0..Pins64WithFunction::MAX_PINS {
+                let next = pin(idx);
+                if pins.last() == Some(&next) {
+                    break;
+                }
+                pins.push(next);
+            }
+        }
+        _ => {}
+    }
+    (function, pins)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{GpioFunction, Rp2350GpioFunction};
+    use alloc::vec;
+
+    pub(crate) const BASE: u32 = FLASH_BASE;
+    const HEADER: u32 = BASE + 0x100;
+    const MAPPINGS: u32 = BASE + 0x120;
+    const ENTRY_TABLE: u32 = BASE + 0x200;
+    const ENTRIES: u32 = BASE + 0x240;
+    const STRINGS: u32 = BASE + 0x340;
+    pub(crate) const RAM_DATA: u32 = BASE + 0x380;
+    pub(crate) const RAM: u32 = 0x2000_0000;
+
+    /// A Flash image we can write words and strings into, at their
+    /// link-time addresses.
+    struct Builder {
+        data: Vec<u8>,
+    }
+
+    impl Builder {
+        fn new() -> Builder {
+            Builder {
+                data: vec![0; 0x400],
+            }
+        }
+
+        fn put(&mut self, address: u32, bytes: &[u8]) {
+            let offset = (address - BASE) as usize;
+            self.data[offset..offset + bytes.len()].copy_from_slice(bytes);
+        }
+
+        fn put_u32s(&mut self, address: u32, words: &[u32]) {
+            for (idx, word) in words.iter().enumerate() {
+                self.put(address + (idx as u32 * 4), &word.to_le_bytes());
+            }
+        }
+
+        fn put_header(&mut self, entries: &[u32]) {
+            self.put_u32s(
+                HEADER,
+                &[
+                    Header::MARKER_START,
+                    ENTRY_TABLE,
+                    ENTRY_TABLE + (entries.len() as u32 * 4),
+                    MAPPINGS,
+                    Header::MARKER_END,
+                ],
+            );
+            self.put_u32s(ENTRY_TABLE, entries);
+        }
+    }
+
+    fn header(data_type: DataType, tag: u16) -> u32 {
+        data_type as u32 | (u32::from(tag) << 16)
+    }
+
+    /// An image with one of each of the kinds of entry that need pointers
+    /// following, with the variables copied to RAM at run-time.
+    ///
+    /// The integer variable is labelled `baud`, and the string variable,
+    /// which has an 8 byte buffer, is labelled `name`.
+    pub(crate) fn sample_image() -> Vec<u8> {
+        let rp = crate::TAG_RASPBERRY_PI;
+        let mut image = Builder::new();
+        image.put_header(&[
+            ENTRIES,
+            ENTRIES + 0x20,
+            ENTRIES + 0x40,
+            ENTRIES + 0x60,
+            ENTRIES + 0x80,
+            ENTRIES + 0xA0,
+        ]);
+        // The variables live at `RAM` and are copied there from `RAM_DATA`
+        image.put_u32s(MAPPINGS, &[RAM_DATA, RAM, RAM + 12, 0, 0, 0]);
+        image.put(STRINGS, b"blinky\0baud\0name\0");
+        image.put_u32s(RAM_DATA, &[-5i32 as u32]);
+        image.put(RAM_DATA + 4, b"dev\0\0\0\0\0");
+
+        // Each entry is laid out as the pico-sdk's `binary_info_*_t` struct
+        // for its data type, which we give the fields of. `core` is the
+        // data type and tag.
+
+        // binary_info_id_and_string_t: core, id, value
+        image.put_u32s(
+            ENTRIES,
+            &[
+                header(DataType::IdAndString, rp),
+                crate::ID_RP_PROGRAM_NAME,
+                STRINGS,
+            ],
+        );
+        // binary_info_id_and_int_t: core, id, value
+        image.put_u32s(
+            ENTRIES + 0x20,
+            &[
+                header(DataType::IdAndInt, rp),
+                crate::ID_RP_BINARY_END,
+                0x1000_2000,
+            ],
+        );
+        // binary_info_ptr_int32_with_name_t: core, id, value, label
+        image.put_u32s(
+            ENTRIES + 0x40,
+            &[
+                header(DataType::PtrInt32WithName, 0x1234),
+                1,
+                RAM,
+                STRINGS + 7,
+            ],
+        );
+        // binary_info_ptr_string_with_name_t: core, id, value, label, len
+        image.put_u32s(
+            ENTRIES + 0x60,
+            &[
+                header(DataType::PtrStringWithName, 0x1234),
+                2,
+                RAM + 4,
+                STRINGS + 12,
+                8,
+            ],
+        );
+        // binary_info_sized_data_t: core, length, then our `PtrInt32Limits`
+        // data - magic, id, value, min, max, bits
+        image.put_u32s(
+            ENTRIES + 0x80,
+            &[
+                header(DataType::SizedData, 0x1234),
+                PtrInt32Limits::LENGTH,
+                PtrInt32Limits::MAGIC,
+                1,
+                RAM,
+                -10i32 as u32,
+                10,
+                5,
+            ],
+        );
+        // binary_info_sized_data_t: core, length, data
+        image.put_u32s(
+            ENTRIES + 0xA0,
+            &[header(DataType::SizedData, 0x1234), 3, 0x0003_0201],
+        );
+        image.data
+    }
+
+    #[test]
+    fn parse_follows_the_mapping_table() {
+        let info = parse(&sample_image(), BASE).unwrap();
+        assert_eq!(info.header_address, HEADER);
+        assert_eq!(
+            info.mapping_table,
+            vec![Mapping {
+                source_addr_start: RAM_DATA,
+                dest_addr_start: RAM,
+                dest_addr_end: RAM + 12,
+            }]
+        );
+        assert_eq!(
+            info.entries,
+            vec![
+                Entry::IdAndString {
+                    tag: crate::TAG_RASPBERRY_PI,
+                    id: crate::ID_RP_PROGRAM_NAME,
+                    value: String::from("blinky"),
+                },
+                Entry::IdAndInt {
+                    tag: crate::TAG_RASPBERRY_PI,
+                    id: crate::ID_RP_BINARY_END,
+                    value: 0x1000_2000,
+                },
+                Entry::PtrInt32WithName {
+                    tag: 0x1234,
+                    id: 1,
+                    label: String::from("baud"),
+                    value: -5,
+                    address: RAM,
+                },
+                Entry::PtrStringWithName {
+                    tag: 0x1234,
+                    id: 2,
+                    label: String::from("name"),
+                    value: String::from("dev"),
+                    max_len: 8,
+                    address: RAM + 4,
+                },
+                Entry::PtrInt32Limits {
+                    tag: 0x1234,
+                    id: 1,
+                    address: RAM,
+                    min: -10,
+                    max: 10,
+                    bits: 5,
+                },
+                Entry::SizedData {
+                    tag: 0x1234,
+                    data: vec![1, 2, 3],
+                },
+            ]
+        );
+        assert_eq!(info.int_limits(RAM), Some((-10, 10, 5)));
+        assert_eq!(
+            info.find_string(crate::TAG_RASPBERRY_PI, crate::ID_RP_PROGRAM_NAME),
+            Some("blinky")
+        );
+        assert_eq!(info.check_placement(Chip::Rp2040, BASE), Ok(()));
+    }
+
+    #[test]
+    fn parse_gives_run_time_entry_addresses() {
+        let mut image = Builder::new();
+        // The entry is copied to `RAM` at run-time, so the Entry Table points
+        // there
+        image.put_header(&[RAM]);
+        image.put_u32s(MAPPINGS, &[RAM_DATA, RAM, RAM + 4, 0, 0, 0]);
+        image.put_u32s(RAM_DATA, &[header(DataType::Raw, 0x1234)]);
+        let info = parse(&image.data, BASE).unwrap();
+        assert_eq!(
+            info.entries,
+            vec![Entry::Raw {
+                tag: 0x1234,
+                address: RAM + 4,
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_rejects_string_buffer_outside_image() {
+        let mut image = Builder {
+            data: sample_image(),
+        };
+        image.put_u32s(ENTRIES + 0x60 + 16, &[0x100]);
+        assert_eq!(
+            parse(&image.data, BASE),
+            Err(Error::OutOfBounds {
+                address: RAM_DATA + 4
+            })
+        );
+    }
+
+    #[test]
+    fn parse_rejects_mapping_that_overflows() {
+        let mut image = Builder {
+            data: sample_image(),
+        };
+        image.put_u32s(MAPPINGS, &[0xFFFF_FFF0, RAM, RAM + 0x100]);
+        assert_eq!(
+            parse(&image.data, BASE),
+            Err(Error::BadMappingTable { address: MAPPINGS })
+        );
+    }
+
+    #[test]
+    fn parse_does_not_overflow_near_top_of_memory() {
+        let image = [0u8; 0x100];
+        assert_eq!(
+            parse(&image, 0xFFFF_FF00),
+            Err(Error::OutOfBounds {
+                address: 0xFFFF_FFF0
+            })
+        );
+        assert_eq!(
+            Chip::Rp2350.header_window(0xFFFF_F800),
+            Err(Error::OutOfBounds {
+                address: 0xFFFF_F800
+            })
+        );
+    }
+
+    #[test]
+    fn decode_pins_round_trips() {
+        for pins in [&[0u8][..], &[2, 3], &[29, 0, 7], &[1, 2, 3, 4, 5]] {
+            let entry = crate::pins_with_function(pins, GpioFunction::Uart);
+            assert_eq!(
+                decode_pins(entry.pin_encoding),
+                (GpioFunction::Uart as u8, pins.to_vec())
+            );
+        }
+        let entry = crate::pin_range_with_function(4, 9, GpioFunction::Pio1);
+        assert_eq!(
+            decode_pins(entry.pin_encoding),
+            (GpioFunction::Pio1 as u8, vec![4, 5, 6, 7, 8, 9])
+        );
+    }
+
+    #[test]
+    fn decode_pins64_round_trips() {
+        let join = |[low, high]: [u32; 2]| u64::from(low) | (u64::from(high) << 32);
+        for pins in [&[47u8][..], &[30, 31], &[1, 2, 3, 4, 5, 6, 7]] {
+            let entry = crate::pins64_with_function(pins, Rp2350GpioFunction::Pio2);
+            let encoding = join(entry.pin_encoding);
+            assert_eq!(
+                decode_pins64(encoding),
+                (Rp2350GpioFunction::Pio2 as u8, pins.to_vec())
+            );
+        }
+        let entry = crate::pin64_range_with_function(40, 47, Rp2350GpioFunction::Uart);
+        let encoding = join(entry.pin_encoding);
+        assert_eq!(
+            decode_pins64(encoding),
+            (Rp2350GpioFunction::Uart as u8, (40..=47).collect())
+        );
+    }
+
+    #[test]
+    fn sparse_image_merges_touching_runs() {
+        let mut image = SparseImage::new();
+        image.insert(0x100, &[1, 2]);
+        image.insert(0x104, &[5, 6]);
+        image.insert(0x102, &[3, 4]);
+        let runs: Vec<(u32, &[u8])> = image.runs().collect();
+        assert_eq!(runs, vec![(0x100, &[1, 2, 3, 4, 5, 6][..])]);
+        assert_eq!(image.read(0x103, 2), Some(&[4, 5][..]));
+    }
+
+    #[test]
+    fn sparse_image_overwrites_overlaps() {
+        let mut image = SparseImage::new();
+        image.insert(0x100, &[1, 2, 3, 4]);
+        image.insert(0x108, &[9, 9]);
+        image.insert(0x102, &[7, 7, 7, 7, 7, 7, 7]);
+        image.insert(0x200, &[8]);
+        let runs: Vec<(u32, &[u8])> = image.runs().collect();
+        assert_eq!(
+            runs,
+            vec![
+                (0x100, &[1, 2, 7, 7, 7, 7, 7, 7, 7, 9][..]),
+                (0x200, &[8][..]),
+            ]
+        );
+        assert_eq!(image.base_address(), Some(0x100));
+        assert_eq!(image.read(0x109, 2), None);
+    }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize_each_kind_of_entry() {
+        use alloc::string::ToString;
+        let rp = crate::TAG_RASPBERRY_PI;
+        let cases = [
+            (
+                Entry::IdAndString {
+                    tag: rp,
+                    id: crate::ID_RP_PROGRAM_NAME,
+                    value: "blinky".to_string(),
+                },
+                r#"{"data_type":"id_and_string","tag":20562,"id":33758342,"name":"program_name","value":"blinky"}"#,
+            ),
+            (
+                Entry::IdAndInt {
+                    tag: 0x1234,
+                    id: 7,
+                    value: 42,
+                },
+                r#"{"data_type":"id_and_int","tag":4660,"id":7,"name":null,"value":42}"#,
+            ),
+            (
+                Entry::PinsWithFunction {
+                    tag: rp,
+                    function: 2,
+                    pins: vec![0, 1],
+                },
+                r#"{"data_type":"pins_with_function","tag":20562,"id":null,"name":null,"value":{"function":2,"pins":[0,1]}}"#,
+            ),
+            (
+                Entry::PinsWithName {
+                    tag: rp,
+                    pin_mask: 0x30,
+                    label: "SDA|SCL".to_string(),
+                },
+                r#"{"data_type":"pins_with_name","tag":20562,"id":null,"name":null,"value":{"pin_mask":48,"label":"SDA|SCL"}}"#,
+            ),
+            (
+                Entry::Pins64WithFunction {
+                    tag: rp,
+                    function: 11,
+                    pins: vec![40, 47],
+                },
+                r#"{"data_type":"pins64_with_function","tag":20562,"id":null,"name":null,"value":{"function":11,"pins":[40,47]}}"#,
+            ),
+            (
+                Entry::Pins64WithName {
+                    tag: rp,
+                    pin_mask: 1 << 40,
+                    label: "LED".to_string(),
+                },
+                r#"{"data_type":"pins64_with_name","tag":20562,"id":null,"name":null,"value":{"pin_mask":1099511627776,"label":"LED"}}"#,
+            ),
+            (
+                Entry::NamedGroup {
+                    parent_tag: rp,
+                    parent_id: crate::ID_RP_PROGRAM_FEATURE,
+                    flags: 1,
+                    group_tag: 0x1234,
+                    group_id: 3,
+                    label: "Config".to_string(),
+                },
+                r#"{"data_type":"named_group","tag":20562,"id":null,"name":null,"value":{"parent_tag":20562,"parent_id":2717168723,"group_tag":4660,"group_id":3,"label":"Config","flags":1}}"#,
+            ),
+            (
+                Entry::BlockDevice {
+                    tag: rp,
+                    name: "fs".to_string(),
+                    address: 0x1010_0000,
+                    size: 0x1000,
+                    flags: 3,
+                },
+                r#"{"data_type":"block_device","tag":20562,"id":null,"name":null,"value":{"name":"fs","address":269484032,"size":4096,"flags":3}}"#,
+            ),
+            (
+                Entry::PtrInt32WithName {
+                    tag: 0x1234,
+                    id: 1,
+                    label: "baud".to_string(),
+                    value: -5,
+                    address: RAM,
+                },
+                r#"{"data_type":"ptr_int32_with_name","tag":4660,"id":1,"name":null,"value":{"label":"baud","value":-5,"address":536870912}}"#,
+            ),
+            (
+                Entry::PtrStringWithName {
+                    tag: 0x1234,
+                    id: 2,
+                    label: "name".to_string(),
+                    value: "dev".to_string(),
+                    max_len: 8,
+                    address: RAM + 4,
+                },
+                r#"{"data_type":"ptr_string_with_name","tag":4660,"id":2,"name":null,"value":{"label":"name","value":"dev","max_len":8,"address":536870916}}"#,
+            ),
+            (
+                Entry::PtrInt32Limits {
+                    tag: 0x1234,
+                    id: 1,
+                    address: RAM,
+                    min: -10,
+                    max: 10,
+                    bits: 5,
+                },
+                r#"{"data_type":"ptr_int32_limits","tag":4660,"id":1,"name":null,"value":{"address":536870912,"min":-10,"max":10,"bits":5}}"#,
+            ),
+            (
+                Entry::Raw {
+                    tag: 0x1234,
+                    address: 0x1000_0400,
+                },
+                r#"{"data_type":"raw","tag":4660,"id":null,"name":null,"value":{"address":268436480}}"#,
+            ),
+            (
+                Entry::SizedData {
+                    tag: 0x1234,
+                    data: vec![1, 2, 3],
+                },
+                r#"{"data_type":"sized_data","tag":4660,"id":null,"name":null,"value":[1,2,3]}"#,
+            ),
+            (
+                Entry::Unknown {
+                    data_type: 99,
+                    tag: 0x1234,
+                    address: 0x1000_0400,
+                },
+                r#"{"data_type":"unknown","tag":4660,"id":null,"name":null,"value":{"data_type":99,"address":268436480}}"#,
+            ),
+        ];
+        for (entry, json) in cases.iter() {
+            assert_eq!(serde_json::to_string(entry).unwrap(), *json);
+        }
+    }
+}
+
+// End of file
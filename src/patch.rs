@@ -0,0 +1,398 @@
+//! Patching
+//!
+//! Types and Functions for changing the default value of a variable pointed
+//! to by a [`PtrInt32WithName`](crate::entry::PtrInt32WithName) or
+//! [`PtrStringWithName`](crate::entry::PtrStringWithName) entry, in an image
+//! which has already been built. This does the same job as
+//! `picotool config -s`.
+//!
+//! The variables live in RAM at run-time, so we use the Mapping Table to work
+//! out where their default values are in Flash, and then change the bytes in
+//! the image. BIN, UF2 and ELF files are all changed in place, so they keep
+//! their size and layout.
+
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+
+use crate::config::int_allowed;
+use crate::parse::{self, BinaryInfo, Entry};
+
+/// A new value for a variable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Value<'a> {
+    /// For a `PtrInt32WithName` entry. Must fit in an `i32`.
+    Int(i64),
+    /// For a `PtrStringWithName` entry. Must be shorter than the variable's
+    /// buffer, to leave room for the null terminator.
+    String(&'a str),
+}
+
+/// Some bytes to write into an image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Patch {
+    /// Where the bytes go, as an address in Flash
+    pub address: u32,
+    /// The bytes to write
+    pub bytes: Vec<u8>,
+}
+
+/// The ways in which patching can fail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// There is no variable with the given label
+    NotFound,
+    /// The variable is an integer and we were given a string, or vice-versa
+    WrongType,
+    /// The integer does not fit in 32 bits
+    OutOfRange,
+    /// The integer is outside the limits recorded for the variable - it must
+    /// be from `min` to `max` inclusive, and fit in `bits` bits
+    OutsideLimits { min: i32, max: i32, bits: u32 },
+    /// The string is too long for the variable's buffer, which holds at most
+    /// `max_len` bytes, including the null terminator
+    TooLong { max_len: u32 },
+    /// The string contains a null
+    ContainsNull,
+    /// Part of the variable's default value is not in the image
+    NotInImage { address: u32 },
+    /// We could not parse the Binary Info in the image
+    Parse(parse::Error),
+    /// We could not read the UF2 file
+    #[cfg(feature = "uf2")]
+    Uf2(crate::uf2::Error),
+    /// We could not read the ELF file
+    #[cfg(feature = "elf")]
+    Elf(crate::elf::Error),
+}
+
+impl Patch {
+    /// Work out what to write to set the variable with the given label to the
+    /// given value.
+    ///
+    /// `info` should come from [`parse`](mod@parse), which checks the whole
+    /// of each string variable's buffer is in the image, so we never
+    /// allocate more than the image holds.
+    pub fn new(info: &BinaryInfo, label: &str, value: Value<'_>) -> Result<Patch, Error> {
+        let entry = find(info, label).ok_or(Error::NotFound)?;
+        let (address, bytes) = match (entry, value) {
+            (Entry::PtrInt32WithName { address, .. }, Value::Int(value)) => {
+                let value = i32::try_from(value).map_err(|_| Error::OutOfRange)?;
+                if let Some((min, max, bits)) = info.int_limits(*address) {
+                    if !int_allowed(i64::from(value), min, max, bits) {
+                        return Err(Error::OutsideLimits { min, max, bits });
+                    }
+                }
+                (*address, value.to_le_bytes().to_vec())
+            }
+            (
+                Entry::PtrStringWithName {
+                    address, max_len, ..
+                },
+                Value::String(value),
+            ) => {
+                if value.as_bytes().contains(&0) {
+                    return Err(Error::ContainsNull);
+                }
+                if value.len() >= *max_len as usize {
+                    return Err(Error::TooLong { max_len: *max_len });
+                }
+                // Fill the whole buffer, so no trace of a longer default
+                // value is left after the null.
+                let mut bytes = value.as_bytes().to_vec();
+                bytes.resize(*max_len as usize, 0);
+                (*address, bytes)
+            }
+            _ => return Err(Error::WrongType),
+        };
+        let address = info
+            .mapping_table
+            .iter()
+            .find_map(|mapping| mapping.resolve(address))
+            .unwrap_or(address);
+        Ok(Patch { address, bytes })
+    }
+
+    /// Write the bytes into a flat image, such as a `.bin` file, where the
+    /// first byte is at `base_address`.
+    pub fn apply(&self, image: &mut [u8], base_address: u32) -> Result<(), Error> {
+        let not_in_image = Error::NotInImage {
+            address: self.address,
+        };
+        let start = self
+            .address
+            .checked_sub(base_address)
+            .ok_or_else(|| not_in_image.clone())? as usize;
+        let end = start
+            .checked_add(self.bytes.len())
+            .ok_or_else(|| not_in_image.clone())?;
+        image
+            .get_mut(start..end)
+            .ok_or(not_in_image)?
+            .copy_from_slice(&self.bytes);
+        Ok(())
+    }
+
+    /// Write the bytes into every main Flash block of a UF2 file which covers
+    /// them.
+    #[cfg(feature = "uf2")]
+    pub fn apply_uf2(&self, data: &mut [u8]) -> Result<(), Error> {
+        use crate::uf2::{Block, BLOCK_SIZE};
+
+        let mut written = alloc::vec![false; self.bytes.len()];
+        for (idx, bytes) in data.chunks_exact_mut(BLOCK_SIZE).enumerate() {
+            let block = Block::parse(bytes, idx).map_err(Error::Uf2)?;
+            if !block.is_main_flash() {
+                continue;
+            }
+            let target_addr = block.target_addr;
+            let payload_size = block.payload_size;
+            self.write_into(&mut bytes[32..], target_addr, payload_size, &mut written);
+        }
+        self.check_written(&written)
+    }
+
+    /// Write the bytes into every loadable segment of an ELF file which
+    /// covers them, using each segment's physical (load) address.
+    #[cfg(feature = "elf")]
+    pub fn apply_elf(&self, data: &mut [u8]) -> Result<(), Error> {
+        let segments = crate::elf::segments(data).map_err(Error::Elf)?;
+        let mut written = alloc::vec![false; self.bytes.len()];
+        for segment in segments.iter().filter(|s| s.is_loaded()) {
+            let start = segment.offset as usize;
+            let bytes = start
+                .checked_add(segment.filesz as usize)
+                .and_then(|end| data.get_mut(start..end))
+                .ok_or(Error::Elf(crate::elf::Error::Truncated))?;
+            self.write_into(bytes, segment.paddr, segment.filesz, &mut written);
+        }
+        self.check_written(&written)
+    }
+
+    /// Write whichever of our bytes fall within the `len` bytes at `address`
+    /// into `dest`, noting which ones we wrote.
+    #[cfg(any(feature = "uf2", feature = "elf"))]
+    fn write_into(&self, dest: &mut [u8], address: u32, len: u32, written: &mut [bool]) {
+        for (idx, byte) in self.bytes.iter().enumerate() {
+            let offset = match self.byte_address(idx) {
+                Some(byte_address) => byte_address.wrapping_sub(address),
+                None => break,
+            };
+            if offset < len {
+                dest[offset as usize] = *byte;
+                written[idx] = true;
+            }
+        }
+    }
+
+    /// Check we wrote every one of our bytes somewhere.
+    #[cfg(any(feature = "uf2", feature = "elf"))]
+    fn check_written(&self, written: &[bool]) -> Result<(), Error> {
+        match written.iter().position(|done| !done) {
+            Some(idx) => Err(Error::NotInImage {
+                address: self.byte_address(idx).unwrap_or(self.address),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Get the address of our byte at `idx`, if it fits in 32 bits.
+    #[cfg(any(feature = "uf2", feature = "elf"))]
+    fn byte_address(&self, idx: usize) -> Option<u32> {
+        u32::try_from(idx)
+            .ok()
+            .and_then(|idx| self.address.checked_add(idx))
+    }
+}
+
+impl From<parse::Error> for Error {
+    fn from(error: parse::Error) -> Error {
+        Error::Parse(error)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotFound => write!(f, "no variable with that name"),
+            Error::WrongType => write!(f, "wrong type of value for that variable"),
+            Error::OutOfRange => write!(f, "value does not fit in 32 bits"),
+            Error::OutsideLimits { min, max, bits } => write!(
+                f,
+                "value must be from {} to {}, and fit in {} bits",
+                min, max, bits
+            ),
+            Error::TooLong { max_len } => write!(
+                f,
+                "string is too long - it must be shorter than {} bytes",
+                max_len
+            ),
+            Error::ContainsNull => write!(f, "string must not contain a null"),
+            Error::NotInImage { address } => {
+                write!(f, "address {:#010x} is not in the image", address)
+            }
+            Error::Parse(error) => error.fmt(f),
+            #[cfg(feature = "uf2")]
+            Error::Uf2(error) => error.fmt(f),
+            #[cfg(feature = "elf")]
+            Error::Elf(error) => error.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Parse(error) => Some(error),
+            #[cfg(feature = "uf2")]
+            Error::Uf2(error) => Some(error),
+            #[cfg(feature = "elf")]
+            Error::Elf(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Find the first `PtrInt32WithName` or `PtrStringWithName` entry with the
+/// given label.
+pub fn find<'a>(info: &'a BinaryInfo, label: &str) -> Option<&'a Entry> {
+    info.entries.iter().find(|entry| match entry {
+        Entry::PtrInt32WithName { label: l, .. } | Entry::PtrStringWithName { label: l, .. } => {
+            l == label
+        }
+        _ => false,
+    })
+}
+
+/// Set the variable with the given label in a flat image, such as a `.bin`
+/// file, where the first byte is at `base_address`.
+pub fn patch_bin(
+    image: &mut [u8],
+    base_address: u32,
+    label: &str,
+    value: Value<'_>,
+) -> Result<(), Error> {
+    let info = parse::parse(image, base_address)?;
+    Patch::new(&info, label, value)?.apply(image, base_address)
+}
+
+/// Set the variable with the given label in a UF2 file.
+#[cfg(feature = "uf2")]
+pub fn patch_uf2(data: &mut [u8], label: &str, value: Value<'_>) -> Result<(), Error> {
+    let info = crate::uf2::parse(data).map_err(Error::Uf2)?;
+    Patch::new(&info, label, value)?.apply_uf2(data)
+}
+
+/// Set the variable with the given label in an ELF file.
+#[cfg(feature = "elf")]
+pub fn patch_elf(data: &mut [u8], label: &str, value: Value<'_>) -> Result<(), Error> {
+    let info = crate::elf::parse(data).map_err(Error::Elf)?;
+    Patch::new(&info, label, value)?.apply_elf(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::tests::{sample_image, BASE, RAM, RAM_DATA};
+    use alloc::vec;
+
+    fn value_of(info: &BinaryInfo, label: &str) -> Entry {
+        find(info, label).unwrap().clone()
+    }
+
+    #[test]
+    fn patch_bin_sets_int() {
+        let mut image = sample_image();
+        patch_bin(&mut image, BASE, "baud", Value::Int(7)).unwrap();
+        let info = parse::parse(&image, BASE).unwrap();
+        assert_eq!(
+            value_of(&info, "baud"),
+            Entry::PtrInt32WithName {
+                tag: 0x1234,
+                id: 1,
+                label: "baud".into(),
+                value: 7,
+                address: RAM,
+            }
+        );
+    }
+
+    #[test]
+    fn patch_bin_sets_string() {
+        let mut image = sample_image();
+        patch_bin(&mut image, BASE, "name", Value::String("pico")).unwrap();
+        let offset = (RAM_DATA + 4 - BASE) as usize;
+        assert_eq!(&image[offset..offset + 8], b"pico\0\0\0\0");
+        patch_bin(&mut image, BASE, "name", Value::String("")).unwrap();
+        assert_eq!(&image[offset..offset + 8], &[0; 8]);
+    }
+
+    #[test]
+    fn patch_checks_values() {
+        let info = parse::parse(&sample_image(), BASE).unwrap();
+        let outside_limits = Err(Error::OutsideLimits {
+            min: -10,
+            max: 10,
+            bits: 5,
+        });
+        assert_eq!(Patch::new(&info, "baud", Value::Int(11)), outside_limits);
+        assert_eq!(Patch::new(&info, "baud", Value::Int(-11)), outside_limits);
+        assert_eq!(
+            Patch::new(&info, "baud", Value::Int(1 << 32)),
+            Err(Error::OutOfRange)
+        );
+        assert_eq!(
+            Patch::new(&info, "name", Value::String("12345678")),
+            Err(Error::TooLong { max_len: 8 })
+        );
+        assert_eq!(
+            Patch::new(&info, "name", Value::String("a\0b")),
+            Err(Error::ContainsNull)
+        );
+        assert_eq!(
+            Patch::new(&info, "name", Value::Int(1)),
+            Err(Error::WrongType)
+        );
+        assert_eq!(
+            Patch::new(&info, "speed", Value::Int(1)),
+            Err(Error::NotFound)
+        );
+    }
+
+    #[test]
+    fn patch_resolves_mapping_table() {
+        let info = parse::parse(&sample_image(), BASE).unwrap();
+        assert_eq!(
+            Patch::new(&info, "baud", Value::Int(-1)),
+            Ok(Patch {
+                address: RAM_DATA,
+                bytes: vec![0xFF; 4],
+            })
+        );
+    }
+
+    #[cfg(feature = "uf2")]
+    #[test]
+    fn patch_uf2_across_block_boundary() {
+        use crate::uf2::tests::to_uf2;
+        use crate::uf2::BLOCK_SIZE;
+
+        // Split the blocks part-way through the string's buffer
+        let payload_size = ((RAM_DATA + 6 - BASE) / 2) as usize;
+        let mut data = to_uf2(&sample_image(), BASE, payload_size);
+        patch_uf2(&mut data, "name", Value::String("pico-2w")).unwrap();
+
+        let info = crate::uf2::parse(&data).unwrap();
+        match value_of(&info, "name") {
+            Entry::PtrStringWithName { value, .. } => assert_eq!(value, "pico-2w"),
+            entry => panic!("unexpected entry {:?}", entry),
+        }
+        let block1 = &data[BLOCK_SIZE + 32..];
+        assert_eq!(&block1[payload_size - 2..payload_size], b"pi");
+        let block2 = &data[(2 * BLOCK_SIZE) + 32..];
+        assert_eq!(&block2[..6], b"co-2w\0");
+    }
+}
+
+// End of file
@@ -0,0 +1,644 @@
+//! Entry Table
+//!
+//! Types and Functions for reading the Entry Table at run-time, on the
+//! device. This lets your program print its own 'Binary Info' (e.g. over
+//! USB-serial at boot) without keeping a second copy of it.
+//!
+//! Unlike [`parse`](crate::parse), we don't need to decode anything - the
+//! Entries are right there in memory, so we just look at the `data_type` in
+//! each one to work out what type it is.
+
+use core::ffi::{c_char, CStr};
+
+use crate::entry::{self, Addr};
+use crate::DataType;
+
+/// An Entry from the Entry Table, with any pointers followed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Entry {
+    /// See [`entry::IdAndString`]
+    IdAndString {
+        tag: u16,
+        id: u32,
+        value: &'static CStr,
+    },
+    /// See [`entry::IdAndInt`] and [`entry::IdAndAddress`]
+    IdAndInt { tag: u16, id: u32, value: u32 },
+    /// See [`entry::PinsWithFunction`]
+    PinsWithFunction { tag: u16, pin_encoding: u32 },
+    /// See [`entry::PinsWithName`]
+    PinsWithName {
+        tag: u16,
+        pin_mask: u32,
+        label: &'static CStr,
+    },
+    /// See [`entry::Pins64WithFunction`]
+    Pins64WithFunction { tag: u16, pin_encoding: u64 },
+    /// See [`entry::Pins64WithName`]
+    Pins64WithName {
+        tag: u16,
+        pin_mask: u64,
+        label: &'static CStr,
+    },
+    /// See [`entry::NamedGroup`]
+    NamedGroup {
+        parent_tag: u16,
+        parent_id: u32,
+        flags: u16,
+        group_tag: u16,
+        group_id: u32,
+        label: &'static CStr,
+    },
+    /// See [`entry::BlockDevice`]
+    BlockDevice {
+        tag: u16,
+        name: &'static CStr,
+        address: u32,
+        size: u32,
+        flags: u16,
+    },
+    /// See [`entry::PtrInt32WithName`]. The value is read when the Entry is.
+    PtrInt32WithName {
+        tag: u16,
+        id: u32,
+        label: &'static CStr,
+        value: i32,
+    },
+    /// See [`entry::PtrStringWithName`]. The value is read when the Entry
+    /// is, and is empty if there is no null in the variable's buffer.
+    PtrStringWithName {
+        tag: u16,
+        id: u32,
+        label: &'static CStr,
+        value: &'static CStr,
+    },
+    /// See [`entry::PtrInt32Limits`]
+    PtrInt32Limits {
+        tag: u16,
+        id: u32,
+        value: *const i32,
+        min: i32,
+        max: i32,
+        bits: u32,
+    },
+    /// See [`entry::Raw`]. We don't know how long the data is, so this
+    /// points at the first byte.
+    Raw { tag: u16, data: *const u8 },
+    /// See [`entry::SizedData`]
+    SizedData { tag: u16, data: &'static [u8] },
+    /// An Entry with a data type we do not know how to read
+    Unknown { data_type: u16, tag: u16 },
+}
+
+/// An iterator over the Entries in an Entry Table.
+#[derive(Debug, Clone)]
+pub struct Entries {
+    next: *const Addr,
+    end: *const Addr,
+}
+
+impl Entries {
+    /// Iterate over the Entry Table from `start` up to (but not including)
+    /// `end`.
+    ///
+    /// # Safety
+    ///
+    /// `start` and `end` must be the start and end of an Entry Table, such
+    /// as the `__bi_entries_start` and `__bi_entries_end` symbols from our
+    /// linker script, and every [`Addr`] in it must point to a valid Entry.
+    pub const unsafe fn new(start: *const Addr, end: *const Addr) -> Entries {
+        Entries { next: start, end }
+    }
+
+    /// Find the first `IdAndString` entry with the given tag and ID.
+    pub fn find_string(mut self, tag: u16, id: u32) -> Option<&'static CStr> {
+        self.find_map(|entry| match entry {
+            Entry::IdAndString {
+                tag: t,
+                id: i,
+                value,
+            } if t == tag && i == id => Some(value),
+            _ => None,
+        })
+    }
+
+    /// Find the first `IdAndInt` entry with the given tag and ID.
+    pub fn find_int(mut self, tag: u16, id: u32) -> Option<u32> {
+        self.find_map(|entry| match entry {
+            Entry::IdAndInt {
+                tag: t,
+                id: i,
+                value,
+            } if t == tag && i == id => Some(value),
+            _ => None,
+        })
+    }
+}
+
+impl Iterator for Entries {
+    type Item = Entry;
+
+    fn next(&mut self) -> Option<Entry> {
+        while self.next < self.end {
+            // Safety: we are within the Entry Table, as promised in `new`
+            let addr = unsafe { &*self.next };
+            // Safety: the Entry Table holds Addrs, so this is still in it, or
+            // just past the end
+            self.next = unsafe { self.next.add(1) };
+            if !addr.as_ptr().is_null() {
+                // Safety: every Addr points to a valid Entry, as promised in
+                // `new`
+                return Some(unsafe { read_entry(addr.as_ptr()) });
+            }
+        }
+        None
+    }
+}
+
+impl Entry {
+    /// Get the tag from this entry's header.
+    ///
+    /// For a `NamedGroup`, this is the tag of the parent group.
+    pub fn tag(&self) -> u16 {
+        match self {
+            Entry::IdAndString { tag, .. }
+            | Entry::IdAndInt { tag, .. }
+            | Entry::PinsWithFunction { tag, .. }
+            | Entry::PinsWithName { tag, .. }
+            | Entry::Pins64WithFunction { tag, .. }
+            | Entry::Pins64WithName { tag, .. }
+            | Entry::BlockDevice { tag, .. }
+            | Entry::PtrInt32WithName { tag, .. }
+            | Entry::PtrStringWithName { tag, .. }
+            | Entry::PtrInt32Limits { tag, .. }
+            | Entry::Raw { tag, .. }
+            | Entry::SizedData { tag, .. }
+            | Entry::Unknown { tag, .. } => *tag,
+            Entry::NamedGroup { parent_tag, .. } => *parent_tag,
+        }
+    }
+
+    /// Get this entry's ID, if it has one.
+    ///
+    /// For a `NamedGroup`, this is the ID of the parent group.
+    pub fn id(&self) -> Option<u32> {
+        match self {
+            Entry::IdAndString { id, .. }
+            | Entry::IdAndInt { id, .. }
+            | Entry::PtrInt32WithName { id, .. }
+            | Entry::PtrStringWithName { id, .. }
+            | Entry::PtrInt32Limits { id, .. } => Some(*id),
+            Entry::NamedGroup { parent_id, .. } => Some(*parent_id),
+            _ => None,
+        }
+    }
+}
+
+/// Iterate over the Entries in this program's Entry Table.
+///
+/// This uses the `__bi_entries_start` and `__bi_entries_end` symbols from our
+/// `binary_info.x` linker script fragment (or your own linker script), so it
+/// is only available when building for the device.
+#[cfg(any(target_os = "none", doc))]
+pub fn entries() -> Entries {
+    extern "C" {
+        static __bi_entries_start: Addr;
+        static __bi_entries_end: Addr;
+    }
+    // Safety: the linker script puts the Entry Table between these symbols,
+    // and only `bi_decl!` (and friends) put Addrs in it
+    unsafe {
+        Entries::new(
+            core::ptr::addr_of!(__bi_entries_start),
+            core::ptr::addr_of!(__bi_entries_end),
+        )
+    }
+}
+
+/// Find the first `IdAndString` entry in this program's Entry Table with the
+/// given tag and ID.
+///
+/// For example, `find_string(TAG_RASPBERRY_PI, ID_RP_PROGRAM_NAME)` gets the
+/// program name, so you don't need to keep another copy of it for your USB
+/// descriptors.
+#[cfg(any(target_os = "none", doc))]
+pub fn find_string(tag: u16, id: u32) -> Option<&'static CStr> {
+    entries().find_string(tag, id)
+}
+
+/// Find the first `IdAndInt` entry in this program's Entry Table with the
+/// given tag and ID.
+#[cfg(any(target_os = "none", doc))]
+pub fn find_int(tag: u16, id: u32) -> Option<u32> {
+    entries().find_int(tag, id)
+}
+
+/// Read the Entry at the given address.
+///
+/// # Safety
+///
+/// `ptr` must point to a valid Entry.
+unsafe fn read_entry(ptr: *const u32) -> Entry {
+    const ID_AND_INT: u16 = DataType::IdAndInt as u16;
+    const ID_AND_STRING: u16 = DataType::IdAndString as u16;
+    const BLOCK_DEVICE: u16 = DataType::BlockDevice as u16;
+    const PINS_WITH_FUNCTION: u16 = DataType::PinsWithFunction as u16;
+    const PINS_WITH_NAME: u16 = DataType::PinsWithName as u16;
+    const NAMED_GROUP: u16 = DataType::NamedGroup as u16;
+    const PINS64_WITH_FUNCTION: u16 = DataType::Pins64WithFunction as u16;
+    const PINS64_WITH_NAME: u16 = DataType::Pins64WithName as u16;
+    const PTR_INT32_WITH_NAME: u16 = DataType::PtrInt32WithName as u16;
+    const PTR_STRING_WITH_NAME: u16 = DataType::PtrStringWithName as u16;
+    const RAW: u16 = DataType::Raw as u16;
+    const SIZED_DATA: u16 = DataType::SizedData as u16;
+
+    // We can't look at the `Common` header until we know the data type is
+    // one we have a `DataType` for, so read it as plain integers first.
+    let data_type = ptr.cast::<u16>().read();
+    let tag = ptr.cast::<u16>().add(1).read();
+    match data_type {
+        ID_AND_INT => {
+            let e = &*ptr.cast::<entry::IdAndInt>();
+            Entry::IdAndInt {
+                tag,
+                id: e.id,
+                value: e.value,
+            }
+        }
+        ID_AND_STRING => {
+            let e = &*ptr.cast::<entry::IdAndString>();
+            Entry::IdAndString {
+                tag,
+                id: e.id,
+                value: cstr(e.value),
+            }
+        }
+        BLOCK_DEVICE => {
+            let e = &*ptr.cast::<entry::BlockDevice>();
+            Entry::BlockDevice {
+                tag,
+                name: cstr(e.name),
+                address: e.address,
+                size: e.size,
+                flags: e.flags,
+            }
+        }
+        PINS_WITH_FUNCTION => {
+            let e = &*ptr.cast::<entry::PinsWithFunction>();
+            Entry::PinsWithFunction {
+                tag,
+                pin_encoding: e.pin_encoding,
+            }
+        }
+        PINS_WITH_NAME => {
+            let e = &*ptr.cast::<entry::PinsWithName>();
+            Entry::PinsWithName {
+                tag,
+                pin_mask: e.pin_mask,
+                label: cstr(e.label),
+            }
+        }
+        NAMED_GROUP => {
+            let e = &*ptr.cast::<entry::NamedGroup>();
+            Entry::NamedGroup {
+                parent_tag: tag,
+                parent_id: e.parent_id,
+                flags: e.flags,
+                group_tag: e.group_tag,
+                group_id: e.group_id,
+                label: cstr(e.label),
+            }
+        }
+        PINS64_WITH_FUNCTION => {
+            let e = &*ptr.cast::<entry::Pins64WithFunction>();
+            Entry::Pins64WithFunction {
+                tag,
+                pin_encoding: join_u64(e.pin_encoding),
+            }
+        }
+        PINS64_WITH_NAME => {
+            let e = &*ptr.cast::<entry::Pins64WithName>();
+            Entry::Pins64WithName {
+                tag,
+                pin_mask: join_u64(e.pin_mask),
+                label: cstr(e.label),
+            }
+        }
+        PTR_INT32_WITH_NAME => {
+            let e = &*ptr.cast::<entry::PtrInt32WithName>();
+            Entry::PtrInt32WithName {
+                tag,
+                id: e.id,
+                label: cstr(e.label),
+                value: e.value.read_volatile(),
+            }
+        }
+        PTR_STRING_WITH_NAME => {
+            let e = &*ptr.cast::<entry::PtrStringWithName>();
+            Entry::PtrStringWithName {
+                tag,
+                id: e.id,
+                label: cstr(e.label),
+                value: cstr_in(e.value, e.len),
+            }
+        }
+        RAW => {
+            // We don't know `N`, but the bytes start in the same place
+            // whatever it is
+            let e = ptr.cast::<entry::Raw<0>>();
+            Entry::Raw {
+                tag,
+                data: core::ptr::addr_of!((*e).bytes).cast::<u8>(),
+            }
+        }
+        SIZED_DATA if is_int_limits(ptr) => {
+            let e = &*ptr.cast::<entry::PtrInt32Limits>();
+            Entry::PtrInt32Limits {
+                tag,
+                id: e.id,
+                value: e.value,
+                min: e.min,
+                max: e.max,
+                bits: e.bits,
+            }
+        }
+        SIZED_DATA => {
+            let e = ptr.cast::<entry::SizedData<0>>();
+            Entry::SizedData {
+                tag,
+                data: core::slice::from_raw_parts(
+                    core::ptr::addr_of!((*e).bytes).cast::<u8>(),
+                    (*e).length as usize,
+                ),
+            }
+        }
+        _ => Entry::Unknown { data_type, tag },
+    }
+}
+
+/// Check whether a `SizedData` entry is really a `PtrInt32Limits` entry.
+///
+/// # Safety
+///
+/// `ptr` must point to a valid `SizedData` entry.
+unsafe fn is_int_limits(ptr: *const u32) -> bool {
+    let e = ptr.cast::<entry::SizedData<0>>();
+    (*e).length == entry::PtrInt32Limits::LENGTH
+        && ptr.add(2).read() == entry::PtrInt32Limits::MAGIC
+}
+
+/// Turn a pointer from an Entry into a string. A null pointer gives an empty
+/// string.
+///
+/// # Safety
+///
+/// `ptr` must be null, or point to a null-terminated string which lives
+/// forever.
+unsafe fn cstr(ptr: *const u8) -> &'static CStr {
+    if ptr.is_null() {
+        Default::default()
+    } else {
+        CStr::from_ptr(ptr.cast::<c_char>())
+    }
+}
+
+/// Turn a pointer to a buffer of `len` bytes into the string at the start of
+/// it. A null pointer, or a buffer with no null in it (e.g. because it has
+/// been badly patched), gives an empty string.
+///
+/// # Safety
+///
+/// `ptr` must be null, or point to `len` bytes which live forever.
+unsafe fn cstr_in(ptr: *const u8, len: u32) -> &'static CStr {
+    if ptr.is_null() {
+        return Default::default();
+    }
+    // Read the pointer with a volatile read, as `ConfigString::get` does, so
+    // the compiler can't assume the buffer still holds its default value
+    let ptr = core::ptr::read_volatile(&ptr);
+    let bytes = core::slice::from_raw_parts(ptr, len as usize);
+    CStr::from_bytes_until_nul(bytes).unwrap_or_default()
+}
+
+/// Join two 32-bit words, low word first, into a 64-bit value.
+fn join_u64(words: [u32; 2]) -> u64 {
+    u64::from(words[0]) | (u64::from(words[1]) << 32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ConfigInt, ConfigString};
+    use crate::{GpioFunction, TAG_RASPBERRY_PI};
+
+    const TAG: u16 = crate::make_tag(b'J', b'P');
+
+    static NAME: entry::IdAndString = crate::program_name("blinky\0");
+    static END: entry::IdAndInt = crate::binary_end(0x1000_2000);
+    static UART: entry::PinsWithFunction = crate::pins_with_function(&[0, 1], GpioFunction::Uart);
+    static I2C: entry::PinsWithName = crate::pins_with_names(&[4, 5], "SDA|SCL\0");
+    static GROUP: entry::NamedGroup = crate::named_group(TAG, 1, TAG, 2, "group\0", 0);
+    static MEMBER: entry::IdAndString = GROUP.member_string("member\0");
+    static BAUD: ConfigInt = ConfigInt::new(-5);
+    static BAUD_ENTRY: entry::PtrInt32WithName =
+        crate::ptr_int32_with_name(TAG, 3, &BAUD, "baud\0");
+    static DEVICE: ConfigString<8> = ConfigString::new("dev");
+    static DEVICE_ENTRY: entry::PtrStringWithName =
+        crate::ptr_string_with_name(TAG, 3, &DEVICE, "device\0");
+
+    static TABLE: [Addr; 10] = [
+        NAME.addr(),
+        END.addr(),
+        UART.addr(),
+        I2C.addr(),
+        Addr::NULL,
+        GROUP.addr(),
+        MEMBER.addr(),
+        BAUD_ENTRY.addr(),
+        DEVICE_ENTRY.addr(),
+        Addr::NULL,
+    ];
+
+    fn cs(bytes: &'static [u8]) -> &'static CStr {
+        CStr::from_bytes_with_nul(bytes).unwrap()
+    }
+
+    fn entries(table: &'static [Addr]) -> Entries {
+        let range = table.as_ptr_range();
+        // Safety: the table only holds Addrs of real entries, or nulls
+        unsafe { Entries::new(range.start, range.end) }
+    }
+
+    #[test]
+    fn entries_in_table_order() {
+        let mut iter = entries(&TABLE);
+        assert_eq!(
+            iter.next(),
+            Some(Entry::IdAndString {
+                tag: TAG_RASPBERRY_PI,
+                id: crate::ID_RP_PROGRAM_NAME,
+                value: cs(b"blinky\0"),
+            })
+        );
+        assert_eq!(
+            iter.next(),
+            Some(Entry::IdAndInt {
+                tag: TAG_RASPBERRY_PI,
+                id: crate::ID_RP_BINARY_END,
+                value: 0x1000_2000,
+            })
+        );
+        assert_eq!(
+            iter.next(),
+            Some(Entry::PinsWithFunction {
+                tag: TAG_RASPBERRY_PI,
+                pin_encoding: UART.pin_encoding,
+            })
+        );
+        assert_eq!(
+            iter.next(),
+            Some(Entry::PinsWithName {
+                tag: TAG_RASPBERRY_PI,
+                pin_mask: 0b11_0000,
+                label: cs(b"SDA|SCL\0"),
+            })
+        );
+        assert_eq!(
+            iter.next(),
+            Some(Entry::NamedGroup {
+                parent_tag: TAG,
+                parent_id: 1,
+                flags: 0,
+                group_tag: TAG,
+                group_id: 2,
+                label: cs(b"group\0"),
+            })
+        );
+        assert_eq!(
+            iter.next(),
+            Some(Entry::IdAndString {
+                tag: TAG,
+                id: 2,
+                value: cs(b"member\0"),
+            })
+        );
+        assert_eq!(
+            iter.next(),
+            Some(Entry::PtrInt32WithName {
+                tag: TAG,
+                id: 3,
+                label: cs(b"baud\0"),
+                value: -5,
+            })
+        );
+        assert_eq!(
+            iter.next(),
+            Some(Entry::PtrStringWithName {
+                tag: TAG,
+                id: 3,
+                label: cs(b"device\0"),
+                value: cs(b"dev\0"),
+            })
+        );
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn empty_table() {
+        static EMPTY: [Addr; 0] = [];
+        assert_eq!(entries(&EMPTY).next(), None);
+    }
+
+    #[test]
+    fn cstr_in_stops_at_buffer() {
+        static BUFFER: [u8; 8] = *b"abc\0efg\0";
+        // Safety: the buffer lives forever, and we never go past its end
+        unsafe {
+            assert_eq!(cstr_in(BUFFER.as_ptr(), 8), cs(b"abc\0"));
+            assert_eq!(cstr_in(BUFFER.as_ptr(), 4), cs(b"abc\0"));
+            // The null is just past the end, so we must not find it
+            assert_eq!(cstr_in(BUFFER.as_ptr(), 3), cs(b"\0"));
+            assert_eq!(cstr_in(BUFFER.as_ptr().add(4), 3), cs(b"\0"));
+            assert_eq!(cstr_in(BUFFER.as_ptr().add(4), 4), cs(b"efg\0"));
+            assert_eq!(cstr_in(core::ptr::null(), 8), cs(b"\0"));
+        }
+    }
+
+    #[test]
+    fn find_string_and_int() {
+        assert_eq!(
+            entries(&TABLE).find_string(TAG_RASPBERRY_PI, crate::ID_RP_PROGRAM_NAME),
+            Some(cs(b"blinky\0"))
+        );
+        assert_eq!(entries(&TABLE).find_string(TAG, 2), Some(cs(b"member\0")));
+        assert_eq!(
+            entries(&TABLE).find_int(TAG_RASPBERRY_PI, crate::ID_RP_BINARY_END),
+            Some(0x1000_2000)
+        );
+        // Right ID, wrong tag
+        assert_eq!(
+            entries(&TABLE).find_string(TAG, crate::ID_RP_PROGRAM_NAME),
+            None
+        );
+        // Right tag and ID, wrong type
+        assert_eq!(
+            entries(&TABLE).find_int(TAG_RASPBERRY_PI, crate::ID_RP_PROGRAM_NAME),
+            None
+        );
+        assert_eq!(entries(&TABLE).find_int(TAG, 3), None);
+    }
+
+    #[test]
+    fn raw_and_sized_data() {
+        static RAW: entry::Raw<3> = crate::raw_data(TAG, &[1, 2, 3]);
+        static SIZED: entry::SizedData<5> = crate::sized_data(TAG, &[4, 5, 6, 7, 8]);
+        static EMPTY: entry::SizedData<0> = crate::sized_data(TAG, &[]);
+        static DATA: [Addr; 3] = [RAW.addr(), SIZED.addr(), EMPTY.addr()];
+
+        let mut iter = entries(&DATA);
+        match iter.next() {
+            Some(Entry::Raw { tag, data }) => {
+                assert_eq!(tag, TAG);
+                // Safety: we know there are three bytes there
+                assert_eq!(unsafe { core::slice::from_raw_parts(data, 3) }, [1, 2, 3]);
+            }
+            entry => panic!("unexpected entry {:?}", entry),
+        }
+        assert_eq!(
+            iter.next(),
+            Some(Entry::SizedData {
+                tag: TAG,
+                data: &[4, 5, 6, 7, 8],
+            })
+        );
+        assert_eq!(
+            iter.next(),
+            Some(Entry::SizedData {
+                tag: TAG,
+                data: &[]
+            })
+        );
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn int_limits_are_not_sized_data() {
+        static LIMITS: entry::PtrInt32Limits = crate::ptr_int32_limits(TAG, 3, &BAUD, -10, 10, 5);
+        static DATA: [Addr; 1] = [LIMITS.addr()];
+
+        assert_eq!(
+            entries(&DATA).next(),
+            Some(Entry::PtrInt32Limits {
+                tag: TAG,
+                id: 3,
+                value: BAUD.as_ptr(),
+                min: -10,
+                max: 10,
+                bits: 5,
+            })
+        );
+    }
+}
+
+// End of file
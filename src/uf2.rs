@@ -0,0 +1,358 @@
+//! UF2
+//!
+//! Types and Functions for reading 'Binary Info' out of a UF2 file, on the
+//! host.
+//!
+//! A UF2 file is a series of 512 byte blocks, each of which carries up to 476
+//! bytes of payload and the address it should be written to. We copy every
+//! payload into a [`SparseImage`] and then hand that to the
+//! [`parse`](mod@parse) module. See
+//! <https://github.com/microsoft/uf2> for the details of the format.
+
+use core::fmt;
+
+use crate::parse::{self, BinaryInfo, Chip, SparseImage};
+
+/// The size of every UF2 block
+pub const BLOCK_SIZE: usize = 512;
+/// The most payload a UF2 block can carry
+pub const MAX_PAYLOAD_SIZE: u32 = 476;
+
+/// The first magic value at the start of every block
+pub const MAGIC_START0: u32 = 0x0A32_4655;
+/// The second magic value at the start of every block
+pub const MAGIC_START1: u32 = 0x9E5D_5157;
+/// The magic value at the end of every block
+pub const MAGIC_END: u32 = 0x0AB1_6F30;
+
+/// This block should not be written to main Flash
+pub const FLAG_NOT_MAIN_FLASH: u32 = 0x0000_0001;
+/// This block is part of a file container, not a Flash image
+pub const FLAG_FILE_CONTAINER: u32 = 0x0000_1000;
+/// The `file_size` field holds a family ID
+pub const FLAG_FAMILY_ID_PRESENT: u32 = 0x0000_2000;
+/// There is an MD5 checksum at the end of the payload
+pub const FLAG_MD5_PRESENT: u32 = 0x0000_4000;
+/// There are extension tags after the payload
+pub const FLAG_EXTENSION_TAGS_PRESENT: u32 = 0x0000_8000;
+
+/// The family ID for RP2040 images
+pub const FAMILY_ID_RP2040: u32 = 0xe48b_ff56;
+/// The family ID for images written to an absolute address on the RP2350
+pub const FAMILY_ID_ABSOLUTE: u32 = 0xe48b_ff57;
+/// The family ID for data (rather than code) on the RP2350
+pub const FAMILY_ID_DATA: u32 = 0xe48b_ff58;
+/// The family ID for RP2350 Arm Secure images
+pub const FAMILY_ID_RP2350_ARM_S: u32 = 0xe48b_ff59;
+/// The family ID for RP2350 RISC-V images
+pub const FAMILY_ID_RP2350_RISCV: u32 = 0xe48b_ff5a;
+/// The family ID for RP2350 Arm Non-Secure images
+pub const FAMILY_ID_RP2350_ARM_NS: u32 = 0xe48b_ff5b;
+
+/// A single decoded UF2 block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block<'a> {
+    pub flags: u32,
+    pub target_addr: u32,
+    pub payload_size: u32,
+    pub block_no: u32,
+    pub num_blocks: u32,
+    /// Either the file size, or the family ID, depending on `flags`
+    pub file_size: u32,
+    /// The payload, which is `payload_size` bytes long
+    pub data: &'a [u8],
+}
+
+/// A Flash image, reassembled from a UF2 file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Uf2Image {
+    /// The family ID of the first block for an RP2040 or RP2350, or failing
+    /// that the first block with a family ID, if any have one
+    pub family_id: Option<u32>,
+    /// Every byte of main Flash which the UF2 file contains
+    pub memory: SparseImage,
+}
+
+/// The ways in which reading a UF2 file can fail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The file is not a whole number of blocks long
+    Truncated,
+    /// The file contains no blocks for main Flash
+    Empty,
+    /// A block had the wrong magic values
+    BadMagic { block: usize },
+    /// A block had a payload which does not fit in a block
+    BadPayloadSize { block: usize },
+    /// We could not parse the Binary Info in the image
+    Parse(parse::Error),
+}
+
+impl<'a> Block<'a> {
+    /// Decode a block from exactly [`BLOCK_SIZE`] bytes.
+    ///
+    /// `block` is only used to report errors.
+    pub fn parse(bytes: &'a [u8], block: usize) -> Result<Block<'a>, Error> {
+        if bytes.len() != BLOCK_SIZE {
+            return Err(Error::Truncated);
+        }
+        let word = |offset: usize| {
+            u32::from_le_bytes([
+                bytes[offset],
+                bytes[offset + 1],
+                bytes[offset + 2],
+                bytes[offset + 3],
+            ])
+        };
+        if word(0) != MAGIC_START0 || word(4) != MAGIC_START1 || word(508) != MAGIC_END {
+            return Err(Error::BadMagic { block });
+        }
+        let payload_size = word(16);
+        if payload_size > MAX_PAYLOAD_SIZE {
+            return Err(Error::BadPayloadSize { block });
+        }
+        Ok(Block {
+            flags: word(8),
+            target_addr: word(12),
+            payload_size,
+            block_no: word(20),
+            num_blocks: word(24),
+            file_size: word(28),
+            data: &bytes[32..32 + payload_size as usize],
+        })
+    }
+
+    /// Get the family ID of this block, if it has one.
+    pub fn family_id(&self) -> Option<u32> {
+        if self.flags & FLAG_FAMILY_ID_PRESENT != 0 {
+            Some(self.file_size)
+        } else {
+            None
+        }
+    }
+
+    /// Should this block be written to main Flash?
+    pub fn is_main_flash(&self) -> bool {
+        self.flags & (FLAG_NOT_MAIN_FLASH | FLAG_FILE_CONTAINER) == 0
+    }
+}
+
+impl Uf2Image {
+    /// Work out which chip this image is for, from its family ID.
+    pub fn chip(&self) -> Option<Chip> {
+        self.family_id.and_then(family_chip)
+    }
+}
+
+impl From<parse::Error> for Error {
+    fn from(error: parse::Error) -> Error {
+        Error::Parse(error)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Truncated => write!(f, "UF2 file is not a whole number of blocks"),
+            Error::Empty => write!(f, "UF2 file has no blocks for main flash"),
+            Error::BadMagic { block } => write!(f, "UF2 block {} has bad magic values", block),
+            Error::BadPayloadSize { block } => {
+                write!(f, "UF2 block {} has a bad payload size", block)
+            }
+            Error::Parse(error) => error.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Parse(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Work out which chip a family ID is for.
+pub fn family_chip(family_id: u32) -> Option<Chip> {
+    match family_id {
+        FAMILY_ID_RP2040 => Some(Chip::Rp2040),
+        FAMILY_ID_RP2350_ARM_S | FAMILY_ID_RP2350_RISCV | FAMILY_ID_RP2350_ARM_NS => {
+            Some(Chip::Rp2350)
+        }
+        _ => None,
+    }
+}
+
+/// Decode every block in a UF2 file.
+pub fn blocks(data: &[u8]) -> impl Iterator<Item = Result<Block<'_>, Error>> {
+    let chunks = data.chunks_exact(BLOCK_SIZE);
+    let truncated = if chunks.remainder().is_empty() {
+        None
+    } else {
+        Some(Err(Error::Truncated))
+    };
+    chunks
+        .enumerate()
+        .map(|(block, bytes)| Block::parse(bytes, block))
+        .chain(truncated)
+}
+
+/// Reassemble the Flash image in a UF2 file.
+///
+/// Blocks which are not for main Flash are skipped.
+///
+/// An RP2350 image may start with a block for [`FAMILY_ID_ABSOLUTE`] (to
+/// work around erratum RP2350-E10), so we take the family ID from the first
+/// block which names a chip, if there is one.
+pub fn read(data: &[u8]) -> Result<Uf2Image, Error> {
+    let mut family_id = None;
+    let mut chip_family_id = None;
+    let mut memory = SparseImage::new();
+    let mut empty = true;
+    for block in blocks(data) {
+        let block = block?;
+        if !block.is_main_flash() {
+            continue;
+        }
+        if empty {
+            family_id = block.family_id();
+            empty = false;
+        }
+        if chip_family_id.is_none() && block.family_id().and_then(family_chip).is_some() {
+            chip_family_id = block.family_id();
+        }
+        memory.insert(block.target_addr, block.data);
+    }
+    if empty {
+        return Err(Error::Empty);
+    }
+    Ok(Uf2Image {
+        family_id: chip_family_id.or(family_id),
+        memory,
+    })
+}
+
+/// Parse the 'Binary Info' in a UF2 file.
+pub fn parse(data: &[u8]) -> Result<BinaryInfo, Error> {
+    let image = read(data)?;
+    let base_address = image.memory.base_address().ok_or(Error::Empty)?;
+    Ok(parse::parse_memory(&image.memory, base_address)?)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::parse::tests::{sample_image, BASE};
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    /// Build a UF2 block carrying `data` to `target_addr`.
+    pub(crate) fn block(flags: u32, target_addr: u32, file_size: u32, data: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(BLOCK_SIZE);
+        for word in [
+            MAGIC_START0,
+            MAGIC_START1,
+            flags,
+            target_addr,
+            data.len() as u32,
+            0,
+            1,
+            file_size,
+        ] {
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+        bytes.extend_from_slice(data);
+        bytes.resize(BLOCK_SIZE - 4, 0);
+        bytes.extend_from_slice(&MAGIC_END.to_le_bytes());
+        bytes
+    }
+
+    /// Convert a flat image into a UF2 file, with `payload_size` bytes in
+    /// each block.
+    pub(crate) fn to_uf2(image: &[u8], base_address: u32, payload_size: usize) -> Vec<u8> {
+        image
+            .chunks(payload_size)
+            .enumerate()
+            .flat_map(|(idx, chunk)| {
+                let target_addr = base_address + (idx * payload_size) as u32;
+                block(FLAG_FAMILY_ID_PRESENT, target_addr, FAMILY_ID_RP2040, chunk)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parse_reassembles_blocks() {
+        let data = to_uf2(&sample_image(), BASE, 256);
+        let image = read(&data).unwrap();
+        assert_eq!(image.chip(), Some(Chip::Rp2040));
+        let runs: Vec<(u32, &[u8])> = image.memory.runs().collect();
+        assert_eq!(runs, vec![(BASE, &sample_image()[..])]);
+        assert_eq!(
+            parse(&data).unwrap(),
+            parse::parse(&sample_image(), BASE).unwrap()
+        );
+    }
+
+    #[test]
+    fn read_skips_blocks_not_for_main_flash() {
+        let mut data = block(FLAG_NOT_MAIN_FLASH, 0x2000_0000, 0, &[1; 4]);
+        data.extend(block(0, BASE, 0, &[2; 4]));
+        let runs: Vec<(u32, Vec<u8>)> = read(&data)
+            .unwrap()
+            .memory
+            .runs()
+            .map(|(address, bytes)| (address, bytes.to_vec()))
+            .collect();
+        assert_eq!(runs, vec![(BASE, vec![2; 4])]);
+        let data = block(FLAG_FILE_CONTAINER, BASE, 0, &[2; 4]);
+        assert_eq!(read(&data), Err(Error::Empty));
+    }
+
+    #[test]
+    fn read_prefers_family_which_names_a_chip() {
+        let mut data = block(FLAG_FAMILY_ID_PRESENT, BASE, FAMILY_ID_ABSOLUTE, &[0; 4]);
+        data.extend(block(
+            FLAG_FAMILY_ID_PRESENT,
+            BASE + 4,
+            FAMILY_ID_RP2350_ARM_S,
+            &[0; 4],
+        ));
+        let image = read(&data).unwrap();
+        assert_eq!(image.family_id, Some(FAMILY_ID_RP2350_ARM_S));
+        assert_eq!(image.chip(), Some(Chip::Rp2350));
+
+        let data = block(FLAG_FAMILY_ID_PRESENT, BASE, FAMILY_ID_ABSOLUTE, &[0; 4]);
+        let image = read(&data).unwrap();
+        assert_eq!(image.family_id, Some(FAMILY_ID_ABSOLUTE));
+        assert_eq!(image.chip(), None);
+    }
+
+    #[test]
+    fn read_rejects_truncated_file() {
+        let data = to_uf2(&sample_image(), BASE, 256);
+        assert_eq!(read(&data[..data.len() - 1]), Err(Error::Truncated));
+        assert_eq!(read(&data[..100]), Err(Error::Truncated));
+        assert_eq!(read(&[]), Err(Error::Empty));
+    }
+
+    #[test]
+    fn read_rejects_bad_magic() {
+        let mut data = to_uf2(&sample_image(), BASE, 256);
+        data[BLOCK_SIZE + 4] ^= 0xFF;
+        assert_eq!(read(&data), Err(Error::BadMagic { block: 1 }));
+        let mut data = to_uf2(&sample_image(), BASE, 256);
+        data[(3 * BLOCK_SIZE) - 1] ^= 0xFF;
+        assert_eq!(read(&data), Err(Error::BadMagic { block: 2 }));
+    }
+
+    #[test]
+    fn read_rejects_oversized_payload() {
+        let mut data = to_uf2(&sample_image(), BASE, 256);
+        data[16..20].copy_from_slice(&(MAX_PAYLOAD_SIZE + 1).to_le_bytes());
+        assert_eq!(read(&data), Err(Error::BadPayloadSize { block: 0 }));
+    }
+}
+
+// End of file